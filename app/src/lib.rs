@@ -1,32 +1,70 @@
 #![no_std]
 
 // necesary crates
-use sails_rs::prelude::*;
+use sails_rs::{
+    prelude::*,
+    gstd::msg,
+};
 
 // import our modules 
 pub mod states;
 pub mod services;
-pub mod utils;
 
 // Import service to be used for the program
 use services::service::Service;
+use states::state::{Config, State};
+use core::cell::RefCell;
 
-pub struct Program;
+// The program owns the state, the services borrow it
+pub struct Program {
+    state: RefCell<State>,
+}
 
 #[program]
 impl Program {
     // Application constructor (it is an associated function)
     // It can be called once per application lifetime.
+    // The account that deploys the program is its admin
     pub fn new() -> Self {
+        Self::new_with_admin(msg::source())
+    }
+
+    // Application constructor that sets another account as the program admin
+    pub fn new_with_admin(admin: ActorId) -> Self {
+        Self::new_with_config(Config {
+            admin,
+            ..Default::default()
+        })
+    }
+
+    // Application constructor with the parameters of the instance (admin,
+    // allowed currencies, limits and fees)
+    pub fn new_with_config(config: Config) -> Self {
+        if config.fees.fee_bps > 10_000 {
+            panic!("Fee cannot be greater than 10000 basis points");
+        }
+        if config.fees.fee_bps > 0 && config.fees.fee_collector.is_zero() {
+            panic!("Fee collector must be set when a fee is charged");
+        }
+
         // Init the state
-        Service::seed();
+        Self {
+            state: RefCell::new(Service::seed(config)),
+        }
+    }
 
-        Self
+    // Application constructor that restores the state exported with the
+    // `ExportState` query of another deployment, older layouts are migrated
+    pub fn new_from_snapshot(snapshot: Vec<u8>) -> Self {
+        // Init the state
+        Self {
+            state: RefCell::new(Service::seed_from_snapshot(snapshot)),
+        }
     }
 
     
     #[route("Service")]
-    pub fn service_svc(&self) -> Service {
-        Service::new()
+    pub fn service_svc(&self) -> Service<'_> {
+        Service::new(&self.state)
     }
 }
\ No newline at end of file
@@ -2,110 +2,1701 @@
 // necesary crates
 use sails_rs::{
     prelude::*,
-    gstd::msg,
+    gstd::{msg, exec, CommandReply},
 };
+use core::cell::{RefCell, RefMut};
 
 // import the state
 use crate::states::*;
 use crate::services::service::state::*;
-use crate::utils::Utils;
+use crate::states::snapshot::VersionedState;
 
-#[derive(Default)]
-pub struct Service;
+// Gas given to the delayed message of every pending occurrence of a recurring
+// expense. The message of an occurrence carries the gas of all the occurrences
+// left, so it can send the next one, which means the message that creates (or
+// resumes) a recurring expense must carry `occurrences` times this amount
+pub const RECURRING_GAS_PER_OCCURRENCE: u64 = 10_000_000_000;
+
+// Blocks that the admins have to approve a payment from the group treasury
+// when the group needs more than one approval (about a day)
+pub const POOL_PAYMENT_EXPIRY_BLOCKS: u32 = 28_800;
+
+// The service borrows the state owned by the program, so the state can be
+// built and used on its own outside of the program
+pub struct Service<'a> {
+    state: &'a RefCell<State>,
+    // Events held back while a batch is applied, they are only emitted if
+    // every action of the batch succeeds
+    pending_events: Option<Vec<ServiceEvents>>,
+}
 
 // Impl for seed related function to init the state
-impl Service {
-    // Related function to init the service state (call only once)
-    pub fn seed() {
-        State::init_state();
+impl<'a> Service<'a> {
+    // Related function to build the initial state of the service
+    pub fn seed(config: Config) -> State {
+        State::new(config)
+    }
+
+    // Related function to init the service state from a snapshot exported by
+    // another deployment (call only once). The active recurring expenses are
+    // scheduled again since their delayed messages were sent to the old program
+    pub fn seed_from_snapshot(snapshot: Vec<u8>) -> State {
+        let state = VersionedState::decode(&mut snapshot.as_slice())
+            .expect("Invalid state snapshot")
+            .migrate(msg::source());
+
+        let now = exec::block_height();
+
+        for group in state.groups.iter() {
+            for recurring in group.recurring_expenses.iter().filter(|r| !r.paused) {
+                let delay = recurring.next_block.saturating_sub(now).max(1);
+                if Self::schedule_recurring_expense(group.id, recurring, delay).is_err() {
+                    panic!("Not enough gas to schedule the recurring expenses");
+                }
+            }
+        }
+
+        state
+    }
+
+    // Related function to send to the program itself the message that adds
+    // the next occurrence of a recurring expense after `delay` blocks, with the
+    // gas of the occurrences left taken from the current message
+    fn schedule_recurring_expense(group_id: u32, recurring: &RecurringExpense, delay: u32) -> Result<(), Events> {
+        let gas_limit = RECURRING_GAS_PER_OCCURRENCE.saturating_mul(recurring.remaining as u64);
+        if exec::gas_available() <= gas_limit {
+            return Err(Events::Error("Not enough gas to schedule the recurring expense".to_owned()));
+        }
+
+        let payload = (
+            "Service",
+            "ProcessRecurringExpense",
+            (group_id, recurring.id, recurring.nonce),
+        ).encode();
+
+        msg::send_bytes_with_gas_delayed(exec::program_id(), payload, gas_limit, 0, delay)
+            .expect("Failed to schedule the recurring expense");
+
+        Ok(())
+    }
+
+    // Method to remove a recurring expense whose next occurrence can not be
+    // added or scheduled, notifying the reason
+    fn stop_recurring_expense(&mut self, group: &mut Group, recurring_id: u32, error: Events) -> Events {
+        group.recurring_expenses.retain(|r| r.id != recurring_id);
+
+        self.emit(ServiceEvents::RecurringExpenseStopped {
+            group_id: group.id,
+            recurring_id,
+            reason: Self::error_reason(error),
+        });
+
+        Events::RecurringExpenseStopped(group.id, recurring_id)
+    }
+
+    // Related function to get the reason of an error reply
+    fn error_reason(event: Events) -> String {
+        match event {
+            Events::Error(reason) => reason,
+            Events::LimitExceeded(limit) => format!("{limit:?}"),
+            _ => String::new(),
+        }
+    }
+
+    // Method to get the state as mutable for the duration of a command, the
+    // borrow is not tied to the service so events can still be emitted
+    fn state_mut(&self) -> RefMut<'a, State> {
+        self.state.borrow_mut()
+    }
+
+    // Method to emit an event, or to hold it back while a batch is applied
+    fn emit(&mut self, event: ServiceEvents) {
+        match self.pending_events.as_mut() {
+            Some(events) => events.push(event),
+            None => self.notify_on(event).expect("Notification Error"),
+        }
+    }
+
+    // Method to reject the commands that change the state while the program
+    // is paused
+    fn check_not_paused(&self) -> Result<(), Events> {
+        if self.state.borrow().paused {
+            return Err(Events::Error("Program is paused".to_owned()));
+        }
+        Ok(())
+    }
+
+    // Related function to get the error of a group that is not in the active
+    // groups, closed groups reject every mutation
+    fn missing_group(state: &State, group_id: u32) -> Events {
+        if state.archived_groups.iter().any(|g| g.id == group_id) {
+            return Events::Error("Group is closed".to_owned());
+        }
+        Events::Error("Group not found".to_owned())
+    }
+
+    // Related function to get the smallest value that a message can carry
+    fn existential_deposit() -> u128 {
+        exec::env_vars().existential_deposit
+    }
+
+    // Related function to send back to every member the funds they still have
+    // in the group treasury, used when the group is closed. Shares below the
+    // existential deposit can not be sent and stay in the program
+    fn refund_pool(group: &mut Group) {
+        let existential_deposit = Self::existential_deposit();
+
+        for balance in group.pool.drain(..) {
+            if balance.amount < existential_deposit {
+                continue;
+            }
+            msg::send_bytes(balance.actor_id, [], balance.amount)
+                .expect("Failed to refund the group treasury");
+        }
+    }
+
+    // Related function to check the length of a text against a limit
+    fn check_length(text: &str, max_length: u32, error: LimitError) -> Result<(), Events> {
+        if text.len() > max_length as usize {
+            return Err(Events::LimitExceeded(error));
+        }
+        Ok(())
+    }
+
+    // Related function to check that a currency is accepted by the program,
+    // every currency is accepted when no allowed currencies are configured
+    fn check_currency(config: &Config, currency: &str) -> Result<(), Events> {
+        if !config.allowed_currencies.is_empty() && !config.allowed_currencies.iter().any(|c| c == currency) {
+            return Err(Events::Error("Currency not allowed".to_owned()));
+        }
+        Ok(())
+    }
+
+    // Related function to check an expense against the configuration
+    fn check_expense_dto(config: &Config, expense_dto: &ExpenseDTO) -> Result<(), Events> {
+        let limits = &config.limits;
+
+        Self::check_currency(config, &expense_dto.currency)?;
+        Self::check_length(&expense_dto.description, limits.max_description_length, LimitError::DescriptionTooLong)?;
+        Self::check_length(&expense_dto.currency, limits.max_currency_length, LimitError::CurrencyTooLong)?;
+
+        if let ExpenseCategory::Other(name) = &expense_dto.category {
+            Self::check_length(name, limits.max_name_length, LimitError::NameTooLong)?;
+        }
+
+        if expense_dto.tags.len() > limits.max_tags as usize {
+            return Err(Events::LimitExceeded(LimitError::TooManyTags));
+        }
+        for tag in expense_dto.tags.iter() {
+            Self::check_length(tag, limits.max_name_length, LimitError::NameTooLong)?;
+        }
+
+        Ok(())
+    }
+
+    // Related function to check that an expense is in the currency of the group
+    fn check_group_currency(group: &Group, currency: &str) -> Result<(), Events> {
+        if group.currency().is_some_and(|c| c != currency) {
+            return Err(Events::Error("Currency does not match the group currency".to_owned()));
+        }
+        Ok(())
+    }
+
+    // Related function to check that a new amount keeps the sum of the group
+    // amounts in an i128, so the balances of the group cannot overflow
+    fn check_ledger_amount(group: &Group, amount: u128) -> Result<(), Events> {
+        if group.ledger_total().saturating_add(amount) > i128::MAX as u128 {
+            return Err(Events::Error("Amount is too large".to_owned()));
+        }
+        Ok(())
+    }
+
+    // Related function to build a new expense of a group paid by one of its
+    // members, it is split between all the members when no participants are
+    // given and it stays pending when the approval policy of the group
+    // requires it
+    fn build_expense(
+        group: &Group,
+        config: &Config,
+        ids: &mut IdCounter,
+        expense_dto: ExpenseDTO,
+        actor_id: ActorId
+    ) -> Result<Expense, Events> {
+        if !group.members.contains(&actor_id) {
+            return Err(Events::Error("User is not a member of the group".to_owned()));
+        }
+        if group.expenses.len() >= config.limits.max_expenses as usize {
+            return Err(Events::LimitExceeded(LimitError::TooManyExpenses));
+        }
+        Self::check_expense_dto(config, &expense_dto)?;
+        Self::check_group_currency(group, &expense_dto.currency)?;
+        Self::check_ledger_amount(group, expense_dto.amount)?;
+
+        let mut expense = Expense::new(0, expense_dto, actor_id);
+
+        if expense.participants.is_empty() {
+            expense.participants = group.members.clone();
+        } else if expense.participants.iter().any(|p| !group.members.contains(p)) {
+            return Err(Events::Error("Participants must be members of the group".to_owned()));
+        }
+
+        if group.approval_policy.requires_approval(expense.amount) {
+            expense.status = ExpenseStatus::Pending;
+        }
+
+        expense.id = ids.next_id();
+        expense.created_block = exec::block_height();
+        expense.created_at = exec::block_timestamp();
+
+        Ok(expense)
+    }
+
+    // Method to add a new expense to the budgets of the group that it matches,
+    // emitting the events of the thresholds that were crossed
+    fn track_budgets(&mut self, state: &mut State, group_id: u32, expense: &Expense) {
+        let now = exec::block_height();
+        let mut events = Vec::new();
+
+        if let Some(group) = state.groups.iter_mut().find(|g| g.id == group_id) {
+            for budget in group.budgets.iter_mut().filter(|b| b.applies_to(expense)) {
+                let (reached, exceeded) = budget.record(expense.id, expense.amount, now);
+                events.extend(Self::budget_events(group_id, budget, reached, exceeded));
+            }
+        }
+
+        for event in events {
+            self.emit(event);
+        }
+    }
+
+    // Method to update the budgets that counted an expense in their current
+    // period after its amount or status changed
+    fn revise_budgets(&mut self, state: &mut State, group_id: u32, expense: &Expense) {
+        let now = exec::block_height();
+        let amount = if expense.counts_in_balances() { expense.amount } else { 0 };
+        let mut events = Vec::new();
+
+        if let Some(group) = state.groups.iter_mut().find(|g| g.id == group_id) {
+            for budget in group.budgets.iter_mut().filter(|b| b.applies_to(expense)) {
+                let (reached, exceeded) = budget.revise(expense.id, amount, now);
+                events.extend(Self::budget_events(group_id, budget, reached, exceeded));
+            }
+        }
+
+        for event in events {
+            self.emit(event);
+        }
+    }
+
+    // Related function to build the events of the thresholds of a budget that
+    // were crossed
+    fn budget_events(group_id: u32, budget: &Budget, reached: Vec<u8>, exceeded: bool) -> Vec<ServiceEvents> {
+        let mut events: Vec<ServiceEvents> = reached
+            .into_iter()
+            .map(|threshold| ServiceEvents::BudgetThresholdReached {
+                group_id,
+                category: budget.category.clone(),
+                threshold,
+                spent: budget.spent,
+                limit: budget.limit,
+            })
+            .collect();
+
+        if exceeded {
+            events.push(ServiceEvents::BudgetExceeded {
+                group_id,
+                category: budget.category.clone(),
+                spent: budget.spent,
+                limit: budget.limit,
+            });
+        }
+
+        events
+    }
+
+    // Related function to add a withdrawal proposal approved by its proposer
+    fn new_withdrawal(
+        state: &mut State,
+        group_id: u32,
+        proposer: ActorId,
+        to: ActorId,
+        amount: u128,
+        expiry_blocks: u32,
+        description: Option<String>
+    ) -> WithdrawalProposal {
+        let proposal = WithdrawalProposal {
+            id: state.ids.next_id(),
+            group_id,
+            proposer,
+            to,
+            amount,
+            approvals: vec![proposer],
+            expires_at: exec::block_height().saturating_add(expiry_blocks),
+            executed: false,
+            description,
+        };
+
+        state.withdrawals.push(proposal.clone());
+
+        proposal
+    }
+
+    // Method to add a payment from the sender to another member, returns the
+    // id of the payment
+    fn record_payment(&mut self, group_id: u32, amount: u32, to: ActorId) -> Result<u32, Events> {
+        self.check_not_paused()?;
+
+        let actor_id = msg::source();
+
+        let state = &mut *self.state_mut();
+
+        let Some(group) = state.groups.iter_mut().find(|g| g.id == group_id) else {
+            return Err(Self::missing_group(state, group_id));
+        };
+
+        if !group.members.contains(&actor_id) {
+            return Err(Events::Error("User is not a member of the group".to_owned()));
+        }
+        if !group.members.contains(&to) {
+            return Err(Events::Error("Recipient is not a member of the group".to_owned()));
+        }
+        if group.payments.len() >= state.config.limits.max_payments as usize {
+            return Err(Events::LimitExceeded(LimitError::TooManyPayments));
+        }
+        Self::check_ledger_amount(group, amount as u128)?;
+
+        let payment = Payment {
+            created_block: exec::block_height(),
+            created_at: exec::block_timestamp(),
+            ..Payment::new(state.ids.next_id(), actor_id, to, amount)
+        };
+        let payment_id = payment.id;
+
+        group.payments.push(payment.clone());
+
+        self.emit(ServiceEvents::PaymentAdded { group_id, payment });
+
+        Ok(payment_id)
     }
+
+    // Method to add a deposit of the sender to the group treasury
+    fn deposit_value(&mut self, group_id: u32, value: u128) -> Events {
+        if let Err(event) = self.check_not_paused() {
+            return event;
+        }
+
+        let actor_id = msg::source();
+
+        if value == 0 {
+            return Events::Error("No value attached to deposit".to_owned());
+        }
+
+        let state = &mut *self.state_mut();
+
+        if let Some(group) = state.groups.iter_mut().find(|g| g.id == group_id) {
+            if !group.members.contains(&actor_id) {
+                return Events::Error("User is not a member of the group".to_owned());
+            }
+
+            // The program fee is sent to the fee collector, a fee below the
+            // existential deposit can not be sent and stays in the program
+            let fees = &state.config.fees;
+            let fee = value.saturating_mul(fees.fee_bps as u128) / 10_000;
+
+            if fee >= Self::existential_deposit() {
+                msg::send_bytes(fees.fee_collector, [], fee)
+                    .expect("Failed to send the deposit fee");
+            }
+
+            group.deposit_to_pool(actor_id, value - fee);
+
+            self.emit(ServiceEvents::Deposited {
+                group_id,
+                actor_id,
+                amount: value - fee,
+                fee,
+            });
+
+            return Events::Deposited(group_id, value - fee);
+        }
+        Self::missing_group(state, group_id)
+    }
+
 }
 
-#[service]
-impl Service {
-    // Service constructor
-    pub fn new() -> Self {
-        Self
+#[service(events = ServiceEvents)]
+impl<'a> Service<'a> {
+    // Service constructor
+    pub fn new(state: &'a RefCell<State>) -> Self {
+        Self {
+            state,
+            pending_events: None,
+        }
+    }
+
+    // Service for the program admin to stop every command that changes the
+    // state, queries are still allowed
+    pub fn pause(&mut self) -> Events {
+        let state = &mut *self.state_mut();
+
+        if msg::source() != state.config.admin {
+            return Events::Error("Only the program admin can pause the program".to_owned());
+        }
+        if state.paused {
+            return Events::Error("Program is paused".to_owned());
+        }
+
+        state.paused = true;
+
+        self.emit(ServiceEvents::ProgramPaused);
+
+        Events::ProgramPaused
+    }
+
+    // Service for the program admin to allow again the commands
+    pub fn unpause(&mut self) -> Events {
+        let state = &mut *self.state_mut();
+
+        if msg::source() != state.config.admin {
+            return Events::Error("Only the program admin can unpause the program".to_owned());
+        }
+        if !state.paused {
+            return Events::Error("Program is not paused".to_owned());
+        }
+
+        state.paused = false;
+
+        self.emit(ServiceEvents::ProgramUnpaused);
+
+        Events::ProgramUnpaused
+    }
+
+    // Service to create a group
+    pub fn create_group(&mut self, group_name: String) -> Events {
+        if let Err(event) = self.check_not_paused() {
+            return event;
+        }
+
+        let wallet = msg::source();
+
+        let state = &mut *self.state_mut();
+
+        if let Err(event) = Self::check_length(&group_name, state.config.limits.max_name_length, LimitError::NameTooLong) {
+            return event;
+        }
+
+        let new_group_id = state.ids.next_id();
+
+        // Logic to create a group
+        state.create_group(new_group_id, group_name.clone(), wallet);
+
+        self.emit(ServiceEvents::GroupCreated {
+            group_id: new_group_id,
+            name: group_name,
+            creator: wallet,
+        });
+
+        // Change State and return event
+        Events::GroupCreated(new_group_id)
+    }
+
+    // Service for a user to join a specific group
+    pub fn join_group(&mut self, group_id: u32) -> Events {
+        if let Err(event) = self.check_not_paused() {
+            return event;
+        }
+
+        let user_id = msg::source();
+        // Validation - check if the group exists
+        let state = &mut *self.state_mut();
+        if let Some(group) = state.groups.iter().find(|g| g.id == group_id) {
+            if group.members.len() >= state.config.limits.max_members as usize {
+                return Events::LimitExceeded(LimitError::TooManyMembers);
+            }
+            if !group.members.contains(&user_id) {
+                // Logic to add a member to the group
+                state.join_group(group_id, user_id);
+
+                self.emit(ServiceEvents::MemberJoined {
+                    group_id,
+                    actor_id: user_id,
+                });
+
+                // Return successful event
+                return Events::UserJoined(user_id, group_id);
+            }
+            return Events::Error("User already in group".to_owned());
+        }
+        Self::missing_group(state, group_id)
+    }
+
+    // Service for an admin to change the information of a group
+    pub fn update_group_metadata(&mut self, group_id: u32, metadata: GroupMetadataDTO) -> Events {
+        if let Err(event) = self.check_not_paused() {
+            return event;
+        }
+
+        let actor_id = msg::source();
+
+        if metadata.name.is_empty() {
+            return Events::Error("Group name cannot be empty".to_owned());
+        }
+
+        let state = &mut *self.state_mut();
+        let limits = &state.config.limits;
+
+        let mut checks = Self::check_length(&metadata.name, limits.max_name_length, LimitError::NameTooLong)
+            .and(Self::check_length(&metadata.description, limits.max_description_length, LimitError::DescriptionTooLong))
+            .and(Self::check_length(&metadata.avatar_uri, limits.max_uri_length, LimitError::UriTooLong))
+            .and(Self::check_length(&metadata.default_currency, limits.max_currency_length, LimitError::CurrencyTooLong));
+
+        if !metadata.default_currency.is_empty() {
+            checks = checks.and(Self::check_currency(&state.config, &metadata.default_currency));
+        }
+
+        if let Err(event) = checks {
+            return event;
+        }
+
+        if let Some(group) = state.groups.iter().find(|g| g.id == group_id) {
+            if !group.admins.contains(&actor_id) {
+                return Events::Error("Only an admin can update the group".to_owned());
+            }
+
+            // The currency of the group is fixed once it has expenses
+            let has_expenses = !group.expenses.is_empty() || !group.recurring_expenses.is_empty();
+            if has_expenses
+                && !metadata.default_currency.is_empty()
+                && group.currency() != Some(metadata.default_currency.as_str())
+            {
+                return Events::Error("Currency of a group with expenses cannot be changed".to_owned());
+            }
+
+            state.update_group_metadata(group_id, metadata.clone());
+
+            self.emit(ServiceEvents::GroupUpdated { group_id, metadata });
+
+            return Events::GroupUpdated(group_id);
+        }
+        Self::missing_group(state, group_id)
+    }
+
+    // Service to add an expense to the group
+    pub fn add_expense(&mut self, group_id: u32, expenseDTO: ExpenseDTO) -> Events {
+        if let Err(event) = self.check_not_paused() {
+            return event;
+        }
+
+        let actor_id = msg::source();
+
+        let state = &mut *self.state_mut();
+
+        // Validation - check if the group exists
+        if let Some(group) = state.groups.iter().find(|g| g.id == group_id) {
+            let expense = match Self::build_expense(group, &state.config, &mut state.ids, expenseDTO, actor_id) {
+                Ok(expense) => expense,
+                Err(event) => return event,
+            };
+
+            // Logic to add an expense
+            state.add_expense(group_id, expense.clone());
+
+            self.emit(ServiceEvents::ExpenseAdded {
+                group_id,
+                expense: expense.clone(),
+            });
+
+            // Pending expenses count in the budgets once they are approved
+            if expense.status == ExpenseStatus::Approved {
+                self.track_budgets(state, group_id, &expense);
+            }
+
+            // Return successful event
+            return Events::ExpenseAdded(group_id, expense.id);
+        }
+        Self::missing_group(state, group_id)
+    }
+
+    // Service to add several expenses paid by the sender in one message, used
+    // to import the history of a group. Either every expense is added or none
+    pub fn add_expenses_batch(&mut self, group_id: u32, expenses: Vec<ExpenseDTO>) -> Events {
+        if let Err(event) = self.check_not_paused() {
+            return event;
+        }
+
+        let actor_id = msg::source();
+
+        if expenses.is_empty() {
+            return Events::Error("Batch cannot be empty".to_owned());
+        }
+
+        let state = &mut *self.state_mut();
+
+        let Some(group) = state.groups.iter().find(|g| g.id == group_id) else {
+            return Self::missing_group(state, group_id);
+        };
+
+        if group.expenses.len() + expenses.len() > state.config.limits.max_expenses as usize {
+            return Events::LimitExceeded(LimitError::TooManyExpenses);
+        }
+
+        // Every expense is checked against the group before the batch is
+        // added, so the batch must not mix currencies or overflow on its own
+        if expenses.iter().any(|e| e.currency != expenses[0].currency) {
+            return Events::Error("Currency does not match the group currency".to_owned());
+        }
+        let total = expenses.iter().fold(0u128, |total, e| total.saturating_add(e.amount));
+        if let Err(event) = Self::check_ledger_amount(group, total) {
+            return event;
+        }
+
+        let mut batch = Vec::with_capacity(expenses.len());
+        for expense_dto in expenses {
+            match Self::build_expense(group, &state.config, &mut state.ids, expense_dto, actor_id) {
+                Ok(expense) => batch.push(expense),
+                Err(event) => return event,
+            }
+        }
+
+        let count = batch.len() as u32;
+
+        for expense in batch {
+            state.add_expense(group_id, expense.clone());
+
+            self.emit(ServiceEvents::ExpenseAdded {
+                group_id,
+                expense: expense.clone(),
+            });
+
+            if expense.status == ExpenseStatus::Approved {
+                self.track_budgets(state, group_id, &expense);
+            }
+        }
+
+        Events::ExpensesBatchAdded(group_id, count)
+    }
+
+    // Service to add an expense that is repeated every `period_blocks` blocks
+    pub fn create_recurring_expense(
+        &mut self,
+        group_id: u32,
+        expense_dto: ExpenseDTO,
+        period_blocks: u32,
+        occurrences: u32
+    ) -> Events {
+        if let Err(event) = self.check_not_paused() {
+            return event;
+        }
+
+        let actor_id = msg::source();
+
+        if period_blocks == 0 || occurrences == 0 {
+            return Events::Error("Period and occurrences must be greater than zero".to_owned());
+        }
+
+        let state = &mut *self.state_mut();
+
+        if let Err(event) = Self::check_expense_dto(&state.config, &expense_dto) {
+            return event;
+        }
+
+        if let Some(group) = state.groups.iter_mut().find(|g| g.id == group_id) {
+            if !group.members.contains(&actor_id) {
+                return Events::Error("User is not a member of the group".to_owned());
+            }
+            if group.recurring_expenses.len() >= state.config.limits.max_recurring_expenses as usize {
+                return Events::LimitExceeded(LimitError::TooManyRecurringExpenses);
+            }
+            if let Err(event) = Self::check_group_currency(group, &expense_dto.currency) {
+                return event;
+            }
+
+            let recurring_id = state.ids.next_id();
+
+            let recurring = RecurringExpense {
+                id: recurring_id,
+                creator: actor_id,
+                expense: expense_dto,
+                period_blocks,
+                remaining: occurrences,
+                paused: false,
+                next_block: exec::block_height().saturating_add(period_blocks),
+                nonce: 0,
+            };
+
+            if let Err(event) = Self::schedule_recurring_expense(group_id, &recurring, period_blocks) {
+                return event;
+            }
+
+            group.recurring_expenses.push(recurring.clone());
+
+            self.emit(ServiceEvents::RecurringExpenseCreated { group_id, recurring });
+
+            return Events::RecurringExpenseCreated(group_id, recurring_id);
+        }
+        Self::missing_group(state, group_id)
+    }
+
+    // Service called by the program itself (delayed message) to add the next
+    // occurrence of a recurring expense. An occurrence that can not be added
+    // stops the recurring expense, and one that comes while the program is
+    // paused pauses it until its creator resumes it
+    pub fn process_recurring_expense(&mut self, group_id: u32, recurring_id: u32, nonce: u32) -> Events {
+        if msg::source() != exec::program_id() {
+            return Events::Error("Only the program can process recurring expenses".to_owned());
+        }
+
+        let paused = self.check_not_paused();
+        let state = &mut *self.state_mut();
+
+        let Some(group) = state.groups.iter_mut().find(|g| g.id == group_id) else {
+            return Self::missing_group(state, group_id);
+        };
+
+        let Some(recurring) = group.recurring_expenses
+            .iter_mut()
+            .find(|r| r.id == recurring_id && r.nonce == nonce && !r.paused) else {
+            return Events::Error("Recurring expense is not active".to_owned());
+        };
+
+        if let Err(event) = paused {
+            recurring.paused = true;
+            recurring.nonce += 1;
+
+            self.emit(ServiceEvents::RecurringExpensePaused { group_id, recurring_id });
+
+            return event;
+        }
+
+        let (expense_dto, creator) = (recurring.expense.clone(), recurring.creator);
+
+        let expense = match Self::build_expense(group, &state.config, &mut state.ids, expense_dto, creator) {
+            Ok(expense) => expense,
+            Err(event) => return self.stop_recurring_expense(group, recurring_id, event),
+        };
+
+        let recurring = group.recurring_expenses
+            .iter_mut()
+            .find(|r| r.id == recurring_id)
+            .expect("Recurring expense not found");
+
+        recurring.remaining -= 1;
+        recurring.next_block = exec::block_height().saturating_add(recurring.period_blocks);
+
+        if recurring.remaining > 0 {
+            if let Err(event) = Self::schedule_recurring_expense(group_id, recurring, recurring.period_blocks) {
+                return self.stop_recurring_expense(group, recurring_id, event);
+            }
+        } else {
+            group.recurring_expenses.retain(|r| r.id != recurring_id);
+        }
+
+        let expense_id = expense.id;
+        group.expenses.push(expense.clone());
+
+        self.emit(ServiceEvents::ExpenseAdded {
+            group_id,
+            expense: expense.clone(),
+        });
+
+        if expense.status == ExpenseStatus::Approved {
+            self.track_budgets(state, group_id, &expense);
+        }
+
+        Events::ExpenseAdded(group_id, expense_id)
+    }
+
+    // Service to stop adding occurrences until the recurring expense is resumed
+    pub fn pause_recurring_expense(&mut self, group_id: u32, recurring_id: u32) -> Events {
+        if let Err(event) = self.check_not_paused() {
+            return event;
+        }
+
+        let actor_id = msg::source();
+        let state = &mut *self.state_mut();
+
+        if let Some(group) = state.groups.iter_mut().find(|g| g.id == group_id) {
+            if let Some(recurring) = group.recurring_expenses.iter_mut().find(|r| r.id == recurring_id) {
+                if recurring.creator != actor_id {
+                    return Events::Error("Only the creator can pause the recurring expense".to_owned());
+                }
+                if recurring.paused {
+                    return Events::Error("Recurring expense already paused".to_owned());
+                }
+
+                recurring.paused = true;
+                recurring.nonce += 1;
+
+                self.emit(ServiceEvents::RecurringExpensePaused { group_id, recurring_id });
+
+                return Events::RecurringExpensePaused(group_id, recurring_id);
+            }
+            return Events::Error("Recurring expense not found".to_owned());
+        }
+        Self::missing_group(state, group_id)
+    }
+
+    // Service to resume a paused recurring expense, the next occurrence is
+    // added after a full period
+    pub fn resume_recurring_expense(&mut self, group_id: u32, recurring_id: u32) -> Events {
+        if let Err(event) = self.check_not_paused() {
+            return event;
+        }
+
+        let actor_id = msg::source();
+        let state = &mut *self.state_mut();
+
+        if let Some(group) = state.groups.iter_mut().find(|g| g.id == group_id) {
+            if let Some(recurring) = group.recurring_expenses.iter_mut().find(|r| r.id == recurring_id) {
+                if recurring.creator != actor_id {
+                    return Events::Error("Only the creator can resume the recurring expense".to_owned());
+                }
+                if !recurring.paused {
+                    return Events::Error("Recurring expense is not paused".to_owned());
+                }
+
+                recurring.paused = false;
+                recurring.nonce += 1;
+                recurring.next_block = exec::block_height().saturating_add(recurring.period_blocks);
+
+                if let Err(event) = Self::schedule_recurring_expense(group_id, recurring, recurring.period_blocks) {
+                    return event;
+                }
+
+                self.emit(ServiceEvents::RecurringExpenseResumed { group_id, recurring_id });
+
+                return Events::RecurringExpenseResumed(group_id, recurring_id);
+            }
+            return Events::Error("Recurring expense not found".to_owned());
+        }
+        Self::missing_group(state, group_id)
+    }
+
+    // Service to remove a recurring expense, pending occurrences are dropped
+    pub fn cancel_recurring_expense(&mut self, group_id: u32, recurring_id: u32) -> Events {
+        if let Err(event) = self.check_not_paused() {
+            return event;
+        }
+
+        let actor_id = msg::source();
+        let state = &mut *self.state_mut();
+
+        if let Some(group) = state.groups.iter_mut().find(|g| g.id == group_id) {
+            if let Some(recurring) = group.recurring_expenses.iter().find(|r| r.id == recurring_id) {
+                if recurring.creator != actor_id {
+                    return Events::Error("Only the creator can cancel the recurring expense".to_owned());
+                }
+
+                group.recurring_expenses.retain(|r| r.id != recurring_id);
+
+                self.emit(ServiceEvents::RecurringExpenseCancelled { group_id, recurring_id });
+
+                return Events::RecurringExpenseCancelled(group_id, recurring_id);
+            }
+            return Events::Error("Recurring expense not found".to_owned());
+        }
+        Self::missing_group(state, group_id)
+    }
+
+    // Service for an admin to set the amount above which expenses need to be
+    // approved and how many approvals they need
+    pub fn set_approval_policy(&mut self, group_id: u32, threshold: Option<u128>, required_approvals: u32) -> Events {
+        if let Err(event) = self.check_not_paused() {
+            return event;
+        }
+
+        let actor_id = msg::source();
+
+        if threshold.is_some() && required_approvals == 0 {
+            return Events::Error("Required approvals must be greater than zero".to_owned());
+        }
+
+        let state = &mut *self.state_mut();
+
+        if let Some(group) = state.groups.iter_mut().find(|g| g.id == group_id) {
+            if !group.admins.contains(&actor_id) {
+                return Events::Error("Only an admin can set the approval policy".to_owned());
+            }
+
+            group.approval_policy = ApprovalPolicy {
+                threshold,
+                required_approvals,
+            };
+
+            self.emit(ServiceEvents::ApprovalPolicySet {
+                group_id,
+                policy: group.approval_policy.clone(),
+            });
+
+            return Events::ApprovalPolicySet(group_id);
+        }
+        Self::missing_group(state, group_id)
+    }
+
+    // Service for a member to approve a pending expense, the expense counts in
+    // the balances once it has the required approvals
+    pub fn approve_expense(&mut self, group_id: u32, expense_id: u32) -> Events {
+        if let Err(event) = self.check_not_paused() {
+            return event;
+        }
+
+        let actor_id = msg::source();
+        let state = &mut *self.state_mut();
+
+        let Some(group) = state.groups.iter_mut().find(|g| g.id == group_id) else {
+            return Self::missing_group(state, group_id);
+        };
+
+        if !group.members.contains(&actor_id) {
+            return Events::Error("User is not a member of the group".to_owned());
+        }
+
+        let required_approvals = group.approval_policy.required_approvals as usize;
+
+        let Some(expense) = group.expenses.iter_mut().find(|e| e.id == expense_id) else {
+            return Events::Error("Expense not found".to_owned());
+        };
+
+        if expense.status != ExpenseStatus::Pending {
+            return Events::Error("Expense is not pending".to_owned());
+        }
+        if expense.actor_id == actor_id {
+            return Events::Error("The payer cannot approve the expense".to_owned());
+        }
+        if expense.approvals.contains(&actor_id) {
+            return Events::Error("Expense already approved by user".to_owned());
+        }
+
+        expense.approvals.push(actor_id);
+
+        if expense.approvals.len() < required_approvals {
+            self.emit(ServiceEvents::ExpenseApprovalAdded {
+                group_id,
+                expense_id,
+                actor_id,
+            });
+
+            return Events::ExpenseApprovalAdded(group_id, expense_id);
+        }
+
+        expense.status = ExpenseStatus::Approved;
+
+        let expense = expense.clone();
+
+        self.emit(ServiceEvents::ExpenseApproved { group_id, expense_id });
+
+        self.track_budgets(state, group_id, &expense);
+
+        Events::ExpenseApproved(group_id, expense_id)
+    }
+
+    // Service for a member to reject a pending expense
+    pub fn reject_expense(&mut self, group_id: u32, expense_id: u32) -> Events {
+        if let Err(event) = self.check_not_paused() {
+            return event;
+        }
+
+        let actor_id = msg::source();
+        let state = &mut *self.state_mut();
+
+        if let Some(group) = state.groups.iter_mut().find(|g| g.id == group_id) {
+            if !group.members.contains(&actor_id) {
+                return Events::Error("User is not a member of the group".to_owned());
+            }
+
+            if let Some(expense) = group.expenses.iter_mut().find(|e| e.id == expense_id) {
+                if expense.status != ExpenseStatus::Pending {
+                    return Events::Error("Expense is not pending".to_owned());
+                }
+                if expense.actor_id == actor_id {
+                    return Events::Error("The payer cannot reject the expense".to_owned());
+                }
+
+                expense.status = ExpenseStatus::Rejected;
+
+                self.emit(ServiceEvents::ExpenseRejected {
+                    group_id,
+                    expense_id,
+                    actor_id,
+                });
+
+                return Events::ExpenseRejected(group_id, expense_id);
+            }
+            return Events::Error("Expense not found".to_owned());
+        }
+        Self::missing_group(state, group_id)
+    }
+
+    // Service for a member affected by an expense (payer or participant) to
+    // dispute it, the expense is frozen out of the balances meanwhile
+    pub fn open_dispute(&mut self, group_id: u32, expense_id: u32, reason: String) -> Events {
+        if let Err(event) = self.check_not_paused() {
+            return event;
+        }
+
+        let actor_id = msg::source();
+        let state = &mut *self.state_mut();
+
+        if let Err(event) = Self::check_length(&reason, state.config.limits.max_description_length, LimitError::DescriptionTooLong) {
+            return event;
+        }
+
+        let Some(group) = state.groups.iter_mut().find(|g| g.id == group_id) else {
+            return Self::missing_group(state, group_id);
+        };
+
+        let Some(expense) = group.expenses.iter().find(|e| e.id == expense_id) else {
+            return Events::Error("Expense not found".to_owned());
+        };
+
+        if expense.actor_id != actor_id && !expense.participants.contains(&actor_id) {
+            return Events::Error("User is not affected by the expense".to_owned());
+        }
+        if matches!(expense.status, ExpenseStatus::Rejected | ExpenseStatus::Voided) {
+            return Events::Error("Expense cannot be disputed".to_owned());
+        }
+        if group.is_disputed(expense_id) {
+            return Events::Error("Expense already disputed".to_owned());
+        }
+        // An expense is disputed once, the resolution of an admin is final
+        if group.disputes.iter().any(|d| d.expense_id == expense_id) {
+            return Events::Error("Dispute of the expense already resolved".to_owned());
+        }
+        if group.disputes.len() >= state.config.limits.max_disputes as usize {
+            return Events::LimitExceeded(LimitError::TooManyDisputes);
+        }
+
+        let dispute_id = state.ids.next_id();
+
+        let dispute = Dispute {
+            id: dispute_id,
+            expense_id,
+            opened_by: actor_id,
+            reason,
+            status: DisputeStatus::Open,
+        };
+
+        group.disputes.push(dispute.clone());
+
+        self.emit(ServiceEvents::DisputeOpened { group_id, dispute });
+
+        Events::DisputeOpened(group_id, dispute_id)
+    }
+
+    // Service for an admin to resolve an open dispute
+    pub fn resolve_dispute(&mut self, group_id: u32, dispute_id: u32, resolution: DisputeResolution) -> Events {
+        if let Err(event) = self.check_not_paused() {
+            return event;
+        }
+
+        let actor_id = msg::source();
+        let state = &mut *self.state_mut();
+
+        let Some(group) = state.groups.iter_mut().find(|g| g.id == group_id) else {
+            return Self::missing_group(state, group_id);
+        };
+
+        if !group.admins.contains(&actor_id) {
+            return Events::Error("Only an admin can resolve a dispute".to_owned());
+        }
+
+        let Some(dispute) = group.disputes.iter().find(|d| d.id == dispute_id) else {
+            return Events::Error("Dispute not found".to_owned());
+        };
+
+        if dispute.status != DisputeStatus::Open {
+            return Events::Error("Dispute already resolved".to_owned());
+        }
+
+        let expense_id = dispute.expense_id;
+        let Some(old_amount) = group.expenses.iter().find(|e| e.id == expense_id).map(|e| e.amount) else {
+            return Events::Error("Expense not found".to_owned());
+        };
+
+        if let DisputeResolution::Amend(amount) = resolution {
+            if amount == 0 {
+                return Events::Error("Amount must be greater than zero".to_owned());
+            }
+            if let Err(event) = Self::check_ledger_amount(group, amount.saturating_sub(old_amount)) {
+                return event;
+            }
+        }
+
+        let requires_approval = matches!(resolution, DisputeResolution::Amend(amount) if group.approval_policy.requires_approval(amount));
+        let Some(expense) = group.expenses.iter_mut().find(|e| e.id == expense_id) else {
+            return Events::Error("Expense not found".to_owned());
+        };
+
+        let status = match resolution {
+            DisputeResolution::Uphold => DisputeStatus::Upheld,
+            DisputeResolution::Amend(amount) => {
+                expense.amount = amount;
+                // A larger amount over the approval threshold is approved again
+                if amount > old_amount && requires_approval {
+                    expense.status = ExpenseStatus::Pending;
+                    expense.approvals.clear();
+                }
+                DisputeStatus::Amended(amount)
+            }
+            DisputeResolution::Void => {
+                expense.status = ExpenseStatus::Voided;
+                DisputeStatus::Voided
+            }
+        };
+        let expense = expense.clone();
+
+        if let Some(dispute) = group.disputes.iter_mut().find(|d| d.id == dispute_id) {
+            dispute.status = status.clone();
+        }
+
+        self.emit(ServiceEvents::DisputeResolved {
+            group_id,
+            dispute_id,
+            status,
+            expense_status: expense.status.clone(),
+        });
+
+        self.revise_budgets(state, group_id, &expense);
+
+        Events::DisputeResolved(group_id, dispute_id)
+    }
+
+    // Service for an admin to set the budget of a group (or of one of its
+    // categories), replacing the previous one
+    pub fn set_budget(
+        &mut self,
+        group_id: u32,
+        category: Option<ExpenseCategory>,
+        currency: String,
+        limit: u128,
+        period_blocks: u32,
+        thresholds: Vec<u8>
+    ) -> Events {
+        if let Err(event) = self.check_not_paused() {
+            return event;
+        }
+
+        let actor_id = msg::source();
+
+        if limit == 0 || period_blocks == 0 {
+            return Events::Error("Limit and period must be greater than zero".to_owned());
+        }
+        if thresholds.iter().any(|t| *t == 0 || *t > 100) {
+            return Events::Error("Thresholds must be percentages between 1 and 100".to_owned());
+        }
+        let mut thresholds = thresholds;
+        thresholds.sort();
+        thresholds.dedup();
+
+        let state = &mut *self.state_mut();
+
+        if let Err(event) = Self::check_currency(&state.config, &currency) {
+            return event;
+        }
+        if let Err(event) = Self::check_length(&currency, state.config.limits.max_currency_length, LimitError::CurrencyTooLong) {
+            return event;
+        }
+
+        if let Some(group) = state.groups.iter_mut().find(|g| g.id == group_id) {
+            if !group.admins.contains(&actor_id) {
+                return Events::Error("Only an admin can set the budget".to_owned());
+            }
+
+            // A budget of the same category and currency is replaced
+            let replaces = group.budgets.iter().any(|b| b.category == category && b.currency == currency);
+            if !replaces && group.budgets.len() >= state.config.limits.max_budgets as usize {
+                return Events::LimitExceeded(LimitError::TooManyBudgets);
+            }
+
+            group.budgets.retain(|b| b.category != category || b.currency != currency);
+            let budget = Budget {
+                category,
+                currency,
+                limit,
+                period_blocks,
+                period_start: exec::block_height(),
+                thresholds,
+                ..Default::default()
+            };
+
+            group.budgets.push(budget.clone());
+
+            self.emit(ServiceEvents::BudgetSet { group_id, budget });
+
+            return Events::BudgetSet(group_id);
+        }
+        Self::missing_group(state, group_id)
     }
 
-    // Service to create a group
-    pub fn create_group(&mut self, group_name: String) -> Events {
+    // Service for an admin to remove a budget of the group
+    pub fn remove_budget(&mut self, group_id: u32, category: Option<ExpenseCategory>, currency: String) -> Events {
+        if let Err(event) = self.check_not_paused() {
+            return event;
+        }
 
-        let wallet = msg::source();
+        let actor_id = msg::source();
+        let state = &mut *self.state_mut();
 
-        let state = State::state_mut();
+        if let Some(group) = state.groups.iter_mut().find(|g| g.id == group_id) {
+            if !group.admins.contains(&actor_id) {
+                return Events::Error("Only an admin can remove the budget".to_owned());
+            }
+            if !group.budgets.iter().any(|b| b.category == category && b.currency == currency) {
+                return Events::Error("Budget not found".to_owned());
+            }
 
-        let new_group_id = Utils::generate_group_id();
+            group.budgets.retain(|b| b.category != category || b.currency != currency);
 
-        // Logic to create a group
-        state.create_group(new_group_id, group_name, wallet);
+            self.emit(ServiceEvents::BudgetRemoved {
+                group_id,
+                category,
+                currency,
+            });
 
-        // Change State and return event
-        Events::GroupCreated(new_group_id)
+            return Events::BudgetRemoved(group_id);
+        }
+        Self::missing_group(state, group_id)
     }
 
-    // Service for a user to join a specific group
-    pub fn join_group(&mut self, group_id: u32) -> Events {
-        let user_id = msg::source();
-        // Validation - check if the group exists
-        let state = State::state_mut();
-        if let Some(group) = state.groups.iter().find(|g| g.id == group_id) {
-            if !group.members.contains(&user_id) {
-                // Logic to add a member to the group
-                state.join_group(group_id, user_id);
+    pub fn add_payment(&mut self, group_id: u32, amount: u32, to: ActorId)->Events{
+        match self.record_payment(group_id, amount, to) {
+            Ok(_) => Events::PaymentAdded(group_id, amount),
+            Err(event) => event,
+        }
+    }
 
-                // Return successful event
-                return Events::UserJoined(user_id, group_id);
+    // Service for the recipient of a payment to confirm that it was received
+    pub fn confirm_payment(&mut self, group_id: u32, payment_id: u32) -> Events {
+        if let Err(event) = self.check_not_paused() {
+            return event;
+        }
+
+        let actor_id = msg::source();
+        let state = &mut *self.state_mut();
+
+        if let Some(group) = state.groups.iter_mut().find(|g| g.id == group_id) {
+            if let Some(payment) = group.payments.iter_mut().find(|p| p.id == payment_id) {
+                if payment.to != actor_id {
+                    return Events::Error("Only the recipient can confirm the payment".to_owned());
+                }
+                if payment.status != PaymentStatus::Unconfirmed {
+                    return Events::Error("Payment is not unconfirmed".to_owned());
+                }
+
+                payment.status = PaymentStatus::Confirmed;
+
+                self.emit(ServiceEvents::PaymentConfirmed { group_id, payment_id });
+
+                return Events::PaymentConfirmed(group_id, payment_id);
             }
-            return Events::Error("User already in group".to_owned());
+            return Events::Error("Payment not found".to_owned());
         }
-        Events::Error("Group not found".to_owned())
+        Self::missing_group(state, group_id)
     }
 
-    // Service to add an expense to the group
-    pub fn add_expense(&mut self, group_id: u32, expenseDTO: ExpenseDTO) -> Events {
+    // Service for the recipient of a payment to reject it when it was not received
+    pub fn reject_payment(&mut self, group_id: u32, payment_id: u32) -> Events {
+        if let Err(event) = self.check_not_paused() {
+            return event;
+        }
+
         let actor_id = msg::source();
-        // Validation - check if the group exists
-        let expense = Expense::new(
-            Utils::generate_group_id(),
-            expenseDTO.description, 
-            expenseDTO.amount, 
-            expenseDTO.currency,
-            actor_id
-        );
+        let state = &mut *self.state_mut();
+
+        if let Some(group) = state.groups.iter_mut().find(|g| g.id == group_id) {
+            if let Some(payment) = group.payments.iter_mut().find(|p| p.id == payment_id) {
+                if payment.to != actor_id {
+                    return Events::Error("Only the recipient can reject the payment".to_owned());
+                }
+                if payment.status != PaymentStatus::Unconfirmed {
+                    return Events::Error("Payment is not unconfirmed".to_owned());
+                }
 
-        let state = State::state_mut();
+                payment.status = PaymentStatus::Rejected;
 
-        if state.groups.iter().any(|g| g.id == group_id) {
-            // Logic to add an expense
-            state.add_expense(group_id, expense.clone());
+                self.emit(ServiceEvents::PaymentRejected { group_id, payment_id });
 
-            // Return successful event
-            return Events::ExpenseAdded(group_id, expense.id);
+                return Events::PaymentRejected(group_id, payment_id);
+            }
+            return Events::Error("Payment not found".to_owned());
         }
-        Events::Error("Group not found".to_owned())
+        Self::missing_group(state, group_id)
     }
 
-    pub fn add_payment(&mut self, group_id: u32, amount: u32, to: ActorId)->Events{
+    // Service to apply several actions on a group in one message. The actions
+    // are applied in order and either all of them succeed or the state is left
+    // unchanged and the reply of the failed action is returned. The reply has
+    // the id of the expense or payment of every action
+    pub fn batch(&mut self, group_id: u32, actions: Vec<GroupAction>) -> Events {
+        if let Err(event) = self.check_not_paused() {
+            return event;
+        }
+
+        if actions.is_empty() {
+            return Events::Error("Batch cannot be empty".to_owned());
+        }
+
+        // The actions only change the group and the id counter, so only them
+        // are kept to undo the batch
+        let (snapshot, ids) = {
+            let state = self.state.borrow();
+            let Some(group) = state.groups.iter().find(|g| g.id == group_id) else {
+                return Self::missing_group(&state, group_id);
+            };
+            (group.clone(), state.ids.clone())
+        };
+
+        let mut applied = Vec::with_capacity(actions.len());
+
+        self.pending_events = Some(Vec::new());
+
+        for action in actions {
+            let result = match action {
+                GroupAction::AddExpense(expense_dto) => match self.add_expense(group_id, expense_dto) {
+                    Events::ExpenseAdded(_, expense_id) => Ok(expense_id),
+                    reply => Err(reply),
+                },
+                GroupAction::AddPayment { amount, to } => self.record_payment(group_id, amount, to),
+                GroupAction::ApproveExpense(expense_id) => match self.approve_expense(group_id, expense_id) {
+                    Events::ExpenseApprovalAdded(..) | Events::ExpenseApproved(..) => Ok(expense_id),
+                    reply => Err(reply),
+                },
+                GroupAction::ConfirmPayment(payment_id) => match self.confirm_payment(group_id, payment_id) {
+                    Events::PaymentConfirmed(..) => Ok(payment_id),
+                    reply => Err(reply),
+                },
+            };
+
+            match result {
+                Ok(id) => applied.push(id),
+                Err(reply) => {
+                    let state = &mut *self.state_mut();
+                    if let Some(group) = state.groups.iter_mut().find(|g| g.id == group_id) {
+                        *group = snapshot;
+                    }
+                    state.ids = ids;
+                    self.pending_events = None;
+
+                    return reply;
+                }
+            }
+        }
+
+        for event in self.pending_events.take().unwrap_or_default() {
+            self.emit(event);
+        }
+
+        Events::BatchApplied(group_id, applied)
+    }
+
+    // Service for a member to deposit the attached value in the group
+    // treasury, the value is sent back with the reply when it is rejected
+    pub fn deposit(&mut self, group_id: u32) -> CommandReply<Events> {
+        let value = msg::value();
+
+        match self.deposit_value(group_id, value) {
+            reply @ Events::Deposited(..) => reply.into(),
+            reply => CommandReply::new(reply).with_value(value),
+        }
+    }
+
+    // Service for an admin to pay a vendor with the group treasury, the payment
+    // is proposed as a withdrawal when the group needs more than one approval
+    pub fn pay_from_pool(&mut self, group_id: u32, vendor: ActorId, amount: u128, description: String) -> Events {
+        if let Err(event) = self.check_not_paused() {
+            return event;
+        }
+
         let actor_id = msg::source();
 
-        let state = State::state_mut();
+        if amount == 0 {
+            return Events::Error("Amount must be greater than zero".to_owned());
+        }
+
+        let state = &mut *self.state_mut();
+
+        if let Err(event) = Self::check_length(&description, state.config.limits.max_description_length, LimitError::DescriptionTooLong) {
+            return event;
+        }
 
         if let Some(group) = state.groups.iter_mut().find(|g| g.id == group_id) {
-            group.payments.push(Payment::new(
-                Utils::generate_group_id(),
-                actor_id,
-                to,
+            if !group.admins.contains(&actor_id) {
+                return Events::Error("Only an admin can pay from the group treasury".to_owned());
+            }
+            if group.pool_total() < amount {
+                return Events::Error("Not enough funds in the group treasury".to_owned());
+            }
+
+            // The payment has to be approved like any other withdrawal
+            if group.required_withdrawal_approvals() > 1 {
+                let proposal = Self::new_withdrawal(
+                    state,
+                    group_id,
+                    actor_id,
+                    vendor,
+                    amount,
+                    POOL_PAYMENT_EXPIRY_BLOCKS,
+                    Some(description)
+                );
+                let proposal_id = proposal.id;
+
+                self.emit(ServiceEvents::WithdrawalProposed { proposal });
+
+                return Events::WithdrawalProposed(group_id, proposal_id);
+            }
+
+            group.debit_pool(amount);
+            let payment = PoolPayment {
+                id: state.ids.next_id(),
+                vendor,
                 amount,
-            ));
-            return Events::PaymentAdded(group_id, amount);
+                description,
+                paid_by: actor_id,
+            };
+
+            group.pool_payments.push(payment.clone());
+
+            msg::send_bytes(vendor, [], amount)
+                .expect("Failed to pay from the group treasury");
+
+            self.emit(ServiceEvents::PoolPaid { group_id, payment });
+
+            return Events::PoolPaid(group_id, amount);
         }
-        Events::Error("Group not found".to_owned())
-        
+        Self::missing_group(state, group_id)
+    }
+
+    // Service for an admin to give admin rights to another member
+    pub fn add_admin(&mut self, group_id: u32, member: ActorId) -> Events {
+        if let Err(event) = self.check_not_paused() {
+            return event;
+        }
+
+        let actor_id = msg::source();
+        let state = &mut *self.state_mut();
+
+        if let Some(group) = state.groups.iter_mut().find(|g| g.id == group_id) {
+            if !group.admins.contains(&actor_id) {
+                return Events::Error("Only an admin can add admins".to_owned());
+            }
+            if !group.members.contains(&member) {
+                return Events::Error("User is not a member of the group".to_owned());
+            }
+            if group.admins.contains(&member) {
+                return Events::Error("User is already an admin".to_owned());
+            }
+
+            group.admins.push(member);
+
+            self.emit(ServiceEvents::AdminAdded {
+                group_id,
+                actor_id: member,
+            });
+
+            return Events::AdminAdded(member, group_id);
+        }
+        Self::missing_group(state, group_id)
+    }
+
+    // Service for an admin to set how many admins must approve a withdrawal
+    pub fn set_withdrawal_quorum(&mut self, group_id: u32, quorum: u32) -> Events {
+        if let Err(event) = self.check_not_paused() {
+            return event;
+        }
+
+        let actor_id = msg::source();
+        let state = &mut *self.state_mut();
+
+        if let Some(group) = state.groups.iter_mut().find(|g| g.id == group_id) {
+            if !group.admins.contains(&actor_id) {
+                return Events::Error("Only an admin can set the withdrawal quorum".to_owned());
+            }
+            if quorum == 0 || quorum as usize > group.admins.len() {
+                return Events::Error("Quorum must be between one and the number of admins".to_owned());
+            }
+
+            group.withdrawal_quorum = quorum;
+
+            self.emit(ServiceEvents::WithdrawalQuorumSet { group_id, quorum });
+
+            return Events::WithdrawalQuorumSet(group_id, quorum);
+        }
+        Self::missing_group(state, group_id)
+    }
+
+    // Service for an admin to propose a withdrawal from the group treasury, the
+    // proposer approves it and it expires after `expiry_blocks` blocks
+    pub fn propose_withdrawal(&mut self, group_id: u32, to: ActorId, amount: u128, expiry_blocks: u32) -> Events {
+        if let Err(event) = self.check_not_paused() {
+            return event;
+        }
+
+        let actor_id = msg::source();
+
+        if amount == 0 || expiry_blocks == 0 {
+            return Events::Error("Amount and expiry must be greater than zero".to_owned());
+        }
+
+        let state = &mut *self.state_mut();
+
+        let Some(group) = state.groups.iter().find(|g| g.id == group_id) else {
+            return Self::missing_group(state, group_id);
+        };
+
+        if !group.admins.contains(&actor_id) {
+            return Events::Error("Only an admin can propose a withdrawal".to_owned());
+        }
+        if group.pool_total() < amount {
+            return Events::Error("Not enough funds in the group treasury".to_owned());
+        }
+
+        let proposal = Self::new_withdrawal(state, group_id, actor_id, to, amount, expiry_blocks, None);
+        let proposal_id = proposal.id;
+
+        self.emit(ServiceEvents::WithdrawalProposed { proposal });
+
+        Events::WithdrawalProposed(group_id, proposal_id)
+    }
+
+    // Service for an admin of the group to approve a withdrawal proposal
+    pub fn approve_withdrawal(&mut self, proposal_id: u32) -> Events {
+        if let Err(event) = self.check_not_paused() {
+            return event;
+        }
+
+        let actor_id = msg::source();
+        let state = &mut *self.state_mut();
+
+        let Some(proposal) = state.withdrawals.iter_mut().find(|w| w.id == proposal_id) else {
+            return Events::Error("Withdrawal proposal not found".to_owned());
+        };
+
+        let group_id = proposal.group_id;
+
+        let Some(group) = state.groups.iter().find(|g| g.id == group_id) else {
+            return Self::missing_group(state, group_id);
+        };
+
+        if !group.admins.contains(&actor_id) {
+            return Events::Error("Only an admin can approve a withdrawal".to_owned());
+        }
+        if proposal.executed {
+            return Events::Error("Withdrawal already executed".to_owned());
+        }
+        if exec::block_height() > proposal.expires_at {
+            return Events::Error("Withdrawal proposal expired".to_owned());
+        }
+        if proposal.approvals.contains(&actor_id) {
+            return Events::Error("Withdrawal already approved by user".to_owned());
+        }
+
+        proposal.approvals.push(actor_id);
+
+        self.emit(ServiceEvents::WithdrawalApproved {
+            group_id: proposal.group_id,
+            proposal_id,
+            actor_id,
+        });
+
+        Events::WithdrawalApproved(proposal.group_id, proposal_id)
+    }
+
+    // Service for an admin of the group to execute a withdrawal proposal that
+    // has enough approvals, the funds are sent to the recipient
+    pub fn execute_withdrawal(&mut self, proposal_id: u32) -> Events {
+        if let Err(event) = self.check_not_paused() {
+            return event;
+        }
+
+        let actor_id = msg::source();
+        let state = &mut *self.state_mut();
+
+        let Some(proposal) = state.withdrawals.iter_mut().find(|w| w.id == proposal_id) else {
+            return Events::Error("Withdrawal proposal not found".to_owned());
+        };
+
+        let group_id = proposal.group_id;
+
+        let Some(group) = state.groups.iter_mut().find(|g| g.id == group_id) else {
+            return Self::missing_group(state, group_id);
+        };
+
+        if !group.admins.contains(&actor_id) {
+            return Events::Error("Only an admin can execute a withdrawal".to_owned());
+        }
+        if proposal.executed {
+            return Events::Error("Withdrawal already executed".to_owned());
+        }
+        if exec::block_height() > proposal.expires_at {
+            return Events::Error("Withdrawal proposal expired".to_owned());
+        }
+        if proposal.approvals.len() < group.required_withdrawal_approvals() {
+            return Events::Error("Withdrawal does not have enough approvals".to_owned());
+        }
+        if group.pool_total() < proposal.amount {
+            return Events::Error("Not enough funds in the group treasury".to_owned());
+        }
+
+        group.debit_pool(proposal.amount);
+        proposal.executed = true;
+
+        if let Some(description) = proposal.description.clone() {
+            group.pool_payments.push(PoolPayment {
+                id: state.ids.next_id(),
+                vendor: proposal.to,
+                amount: proposal.amount,
+                description,
+                paid_by: proposal.proposer,
+            });
+        }
+
+        msg::send_bytes(proposal.to, [], proposal.amount)
+            .expect("Failed to send the withdrawal");
+
+        self.emit(ServiceEvents::WithdrawalExecuted {
+            group_id: proposal.group_id,
+            proposal_id,
+            to: proposal.to,
+            amount: proposal.amount,
+        });
+
+        Events::WithdrawalExecuted(proposal.group_id, proposal_id)
+    }
+
+    // Service for an admin to close a group. The balances must be settled
+    // unless `force` is set, in which case the settlement plan at closing time
+    // is kept in the archive. The treasury is refunded to the members and the
+    // group is moved to the archived groups
+    pub fn close_group(&mut self, group_id: u32, force: bool) -> Events {
+        if let Err(event) = self.check_not_paused() {
+            return event;
+        }
+
+        let actor_id = msg::source();
+        let state = &mut *self.state_mut();
+
+        let Some(group) = state.groups.iter_mut().find(|g| g.id == group_id) else {
+            return Self::missing_group(state, group_id);
+        };
+
+        if !group.admins.contains(&actor_id) {
+            return Events::Error("Only an admin can close the group".to_owned());
+        }
+        if !force && group.balances().iter().any(|b| b.amount != 0) {
+            return Events::Error("Group balances are not settled".to_owned());
+        }
+
+        Self::refund_pool(group);
+
+        state.close_group(group_id, exec::block_height());
+
+        self.emit(ServiceEvents::GroupClosed { group_id, forced: force });
+
+        Events::GroupClosed(group_id)
     }
 
     pub fn query_group(&self, groupId: u32)->Group{
-        State::state_ref()
+        self.state.borrow()
         .groups
         .iter()
         .find(|x| x.id == groupId) // Correct closure
@@ -116,7 +1707,7 @@ impl Service {
     pub fn query_actor_groups(&self) -> Vec<Group> {
         let actor_id = msg::source(); 
 
-        State::state_ref()
+        self.state.borrow()
             .groups
             .iter()
             .filter(|group| group.members.contains(&actor_id)) // Filter groups where actor_id is in the members list
@@ -126,7 +1717,7 @@ impl Service {
 
     // Queried function to get group members by group id
     pub fn query_group_members(&self, group_id: u32) -> Option<Vec<ActorId>> {
-        State::state_ref()
+        self.state.borrow()
             .groups
             .iter()
             .find(|g| g.id == group_id)
@@ -135,17 +1726,136 @@ impl Service {
 
     // Queried function to get expenses for a group
     pub fn query_expenses(&self, group_id: u32) -> Option<Vec<Expense>> {
-        State::state_ref()
+        self.state.borrow()
             .groups
             .iter()
             .find(|g| g.id == group_id)
             .map(|g| g.expenses.clone())
     }
 
+    // Queried function to get the balance of every member of a group
+    pub fn query_balances(&self, group_id: u32) -> Option<Vec<Balance>> {
+        self.state.borrow()
+            .groups
+            .iter()
+            .find(|g| g.id == group_id)
+            .map(|g| g.balances())
+    }
+
+    // Queried function to get the expenses and payments that make up the
+    // balance of a member of a group
+    pub fn query_member_statement(&self, group_id: u32, actor_id: ActorId) -> Option<MemberStatement> {
+        self.state.borrow()
+            .groups
+            .iter()
+            .find(|g| g.id == group_id && g.members.contains(&actor_id))
+            .map(|g| g.member_statement(actor_id))
+    }
+
+    // Queried function to get the disputes of a group
+    pub fn query_disputes(&self, group_id: u32) -> Option<Vec<Dispute>> {
+        self.state.borrow()
+            .groups
+            .iter()
+            .find(|g| g.id == group_id)
+            .map(|g| g.disputes.clone())
+    }
+
+    // Queried function to get payments for a group
+    pub fn query_payments(&self, group_id: u32) -> Option<Vec<Payment>> {
+        self.state.borrow()
+            .groups
+            .iter()
+            .find(|g| g.id == group_id)
+            .map(|g| g.payments.clone())
+    }
+
+    // Queried function to get the funds of every member in the group treasury
+    pub fn query_pool(&self, group_id: u32) -> Option<Vec<PoolBalance>> {
+        self.state.borrow()
+            .groups
+            .iter()
+            .find(|g| g.id == group_id)
+            .map(|g| g.pool.clone())
+    }
+
+    // Queried function to get the withdrawal proposals of a group
+    pub fn query_withdrawals(&self, group_id: u32) -> Vec<WithdrawalProposal> {
+        self.state.borrow()
+            .withdrawals
+            .iter()
+            .filter(|w| w.group_id == group_id)
+            .cloned()
+            .collect()
+    }
+
+    // Queried function to get the transfers that settle the balances of a group
+    pub fn query_settlement_plan(&self, group_id: u32) -> Option<Vec<Transfer>> {
+        self.state.borrow()
+            .groups
+            .iter()
+            .find(|g| g.id == group_id)
+            .map(|g| g.settlement_plan())
+    }
+
+    // Queried function to get a closed group
+    pub fn query_archived_group(&self, group_id: u32) -> Option<ArchivedGroup> {
+        self.state.borrow()
+            .archived_groups
+            .iter()
+            .find(|g| g.id == group_id)
+            .cloned()
+    }
+
+    // Queried function to get the configuration of the program
+    pub fn query_config(&self) -> Config {
+        self.state.borrow()
+            .config
+            .clone()
+    }
+
+    // Queried function to export the state as a versioned SCALE blob that
+    // `NewFromSnapshot` can restore. Queries are not authenticated and the
+    // state is public (see `Query`), so anyone can export it
+    pub fn export_state(&self) -> Vec<u8> {
+        let state = self.state.borrow();
+
+        VersionedState::current(state.clone()).encode()
+    }
+
+    // Queried function to get the spending of a group by category
+    pub fn query_category_totals(&self, group_id: u32) -> Option<Vec<CategoryTotal>> {
+        self.state.borrow()
+            .groups
+            .iter()
+            .find(|g| g.id == group_id)
+            .map(|g| g.category_totals())
+    }
+
+    // Queried function to get the active recurring expenses of a group
+    pub fn query_recurring_expenses(&self, group_id: u32) -> Option<Vec<RecurringExpense>> {
+        self.state.borrow()
+            .groups
+            .iter()
+            .find(|g| g.id == group_id)
+            .map(|g| g.recurring_expenses.clone())
+    }
+
+    // Queried function to get the spending of the budgets of a group
+    pub fn query_budget_status(&self, group_id: u32) -> Option<Vec<BudgetStatus>> {
+        let now = exec::block_height();
+
+        self.state.borrow()
+            .groups
+            .iter()
+            .find(|g| g.id == group_id)
+            .map(|g| g.budgets.iter().map(|b| b.status(now)).collect())
+    }
+
     // Returns a struct that will be sent as a response to the user
     pub fn query(&self) -> IoState {
-        State::state_ref()
-            .to_owned()
+        self.state.borrow()
+            .clone()
             .into()
     }
 }
@@ -159,7 +1869,214 @@ pub enum Events {
     UserJoined(ActorId, u32),
     ExpenseAdded(u32, u32),
     PaymentAdded(u32, u32),
+    RecurringExpenseCreated(u32, u32),
+    RecurringExpensePaused(u32, u32),
+    RecurringExpenseResumed(u32, u32),
+    RecurringExpenseCancelled(u32, u32),
+    RecurringExpenseStopped(u32, u32),
+    BudgetSet(u32),
+    BudgetRemoved(u32),
+    ApprovalPolicySet(u32),
+    ExpenseApprovalAdded(u32, u32),
+    ExpenseApproved(u32, u32),
+    ExpenseRejected(u32, u32),
+    DisputeOpened(u32, u32),
+    DisputeResolved(u32, u32),
+    PaymentConfirmed(u32, u32),
+    PaymentRejected(u32, u32),
+    Deposited(u32, u128),
+    PoolPaid(u32, u128),
+    AdminAdded(ActorId, u32),
+    WithdrawalQuorumSet(u32, u32),
+    WithdrawalProposed(u32, u32),
+    WithdrawalApproved(u32, u32),
+    WithdrawalExecuted(u32, u32),
+    GroupClosed(u32),
+    GroupUpdated(u32),
+    ExpensesBatchAdded(u32, u32),
+    BatchApplied(u32, Vec<u32>),
+    LimitExceeded(LimitError),
+    ProgramPaused,
+    ProgramUnpaused,
     Error(String),
 }
 
+// Actions that can be applied together with the `batch` command
+#[derive(Encode, Decode, TypeInfo, Clone)]
+#[codec(crate = sails_rs::scale_codec)]
+#[scale_info(crate = sails_rs::scale_info)]
+pub enum GroupAction {
+    AddExpense(ExpenseDTO),
+    AddPayment {
+        amount: u32,
+        to: ActorId,
+    },
+    ApproveExpense(u32),
+    ConfirmPayment(u32),
+}
+
+// Enum with the limits that the input of a user can exceed
+#[derive(Encode, Decode, TypeInfo, Debug)]
+#[codec(crate = sails_rs::scale_codec)]
+#[scale_info(crate = sails_rs::scale_info)]
+pub enum LimitError {
+    NameTooLong,
+    DescriptionTooLong,
+    CurrencyTooLong,
+    UriTooLong,
+    TooManyTags,
+    TooManyMembers,
+    TooManyExpenses,
+    TooManyPayments,
+    TooManyDisputes,
+    TooManyBudgets,
+    TooManyRecurringExpenses,
+}
+
+// Events emitted by the service to notify observers, they carry enough data
+// for an off-chain indexer to rebuild the ledger without querying the state
+#[derive(Encode, Decode, TypeInfo)]
+#[codec(crate = sails_rs::scale_codec)]
+#[scale_info(crate = sails_rs::scale_info)]
+pub enum ServiceEvents {
+    ProgramPaused,
+    ProgramUnpaused,
+    GroupCreated {
+        group_id: u32,
+        name: String,
+        creator: ActorId,
+    },
+    MemberJoined {
+        group_id: u32,
+        actor_id: ActorId,
+    },
+    GroupUpdated {
+        group_id: u32,
+        metadata: GroupMetadataDTO,
+    },
+    AdminAdded {
+        group_id: u32,
+        actor_id: ActorId,
+    },
+    GroupClosed {
+        group_id: u32,
+        forced: bool,
+    },
+    ExpenseAdded {
+        group_id: u32,
+        expense: Expense,
+    },
+    RecurringExpenseCreated {
+        group_id: u32,
+        recurring: RecurringExpense,
+    },
+    RecurringExpensePaused {
+        group_id: u32,
+        recurring_id: u32,
+    },
+    RecurringExpenseResumed {
+        group_id: u32,
+        recurring_id: u32,
+    },
+    RecurringExpenseCancelled {
+        group_id: u32,
+        recurring_id: u32,
+    },
+    RecurringExpenseStopped {
+        group_id: u32,
+        recurring_id: u32,
+        reason: String,
+    },
+    ApprovalPolicySet {
+        group_id: u32,
+        policy: ApprovalPolicy,
+    },
+    ExpenseApprovalAdded {
+        group_id: u32,
+        expense_id: u32,
+        actor_id: ActorId,
+    },
+    ExpenseApproved {
+        group_id: u32,
+        expense_id: u32,
+    },
+    ExpenseRejected {
+        group_id: u32,
+        expense_id: u32,
+        actor_id: ActorId,
+    },
+    DisputeOpened {
+        group_id: u32,
+        dispute: Dispute,
+    },
+    DisputeResolved {
+        group_id: u32,
+        dispute_id: u32,
+        status: DisputeStatus,
+        expense_status: ExpenseStatus,
+    },
+    BudgetSet {
+        group_id: u32,
+        budget: Budget,
+    },
+    BudgetRemoved {
+        group_id: u32,
+        category: Option<ExpenseCategory>,
+        currency: String,
+    },
+    PaymentAdded {
+        group_id: u32,
+        payment: Payment,
+    },
+    PaymentConfirmed {
+        group_id: u32,
+        payment_id: u32,
+    },
+    PaymentRejected {
+        group_id: u32,
+        payment_id: u32,
+    },
+    Deposited {
+        group_id: u32,
+        actor_id: ActorId,
+        amount: u128,
+        fee: u128,
+    },
+    PoolPaid {
+        group_id: u32,
+        payment: PoolPayment,
+    },
+    WithdrawalQuorumSet {
+        group_id: u32,
+        quorum: u32,
+    },
+    WithdrawalProposed {
+        proposal: WithdrawalProposal,
+    },
+    WithdrawalApproved {
+        group_id: u32,
+        proposal_id: u32,
+        actor_id: ActorId,
+    },
+    WithdrawalExecuted {
+        group_id: u32,
+        proposal_id: u32,
+        to: ActorId,
+        amount: u128,
+    },
+    BudgetThresholdReached {
+        group_id: u32,
+        category: Option<ExpenseCategory>,
+        threshold: u8,
+        spent: u128,
+        limit: u128,
+    },
+    BudgetExceeded {
+        group_id: u32,
+        category: Option<ExpenseCategory>,
+        spent: u128,
+        limit: u128,
+    },
+}
+
 //This code adds services for creating a group, joining a group, and adding an expense, along with query methods to retrieve admins, group members, and expenses. It includes validation checks to ensure logical consistency, such as checking if a group already exists or if a user is already part of a group before joining.  m 
\ No newline at end of file
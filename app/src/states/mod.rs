@@ -1 +1,2 @@
-pub mod state;
\ No newline at end of file
+pub mod state;
+pub mod snapshot;
\ No newline at end of file
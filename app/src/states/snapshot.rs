@@ -0,0 +1,136 @@
+// snapshot.rs
+// Use necessary crates
+use sails_rs::{
+    prelude::*,
+};
+
+use super::state::*;
+
+// Enum with every layout of the state that can be restored in a new
+// deployment. A snapshot is the SCALE encoding of this enum, so its first
+// byte is the version of the layout. Only the last variant uses the types of
+// the live state, every other variant has its own copy of the types of its
+// release. Before the layout of the live state changes, its types are copied
+// into a new frozen version and a new last variant is added
+#[derive(Encode, Decode)]
+#[codec(crate = sails_rs::scale_codec)]
+pub enum VersionedState {
+    // Layout of the first release, which had no export. Its snapshot is the
+    // reply of the `Query` query of that release prefixed with a zero byte
+    V0(StateV0),
+    // Layout of the release that added the export, the current one
+    V1(State),
+}
+
+impl VersionedState {
+    // Related function to build a snapshot of the current state
+    pub fn current(state: State) -> Self {
+        VersionedState::V1(state)
+    }
+
+    // Method to migrate a snapshot to the current layout. `admin` is the
+    // program admin used for layouts that did not store one
+    pub fn migrate(self, admin: ActorId) -> State {
+        match self {
+            VersionedState::V0(state) => state.migrate(admin),
+            VersionedState::V1(state) => state,
+        }
+    }
+}
+
+// Structs with the layout of the first release
+#[derive(Encode, Decode)]
+#[codec(crate = sails_rs::scale_codec)]
+pub struct StateV0 {
+    pub groups: Vec<GroupV0>,
+}
+
+#[derive(Encode, Decode)]
+#[codec(crate = sails_rs::scale_codec)]
+pub struct GroupV0 {
+    pub id: u32,
+    pub name: String,
+    pub members: Vec<ActorId>,
+    pub expenses: Vec<ExpenseV0>,
+    pub payments: Vec<PaymentV0>,
+}
+
+#[derive(Encode, Decode)]
+#[codec(crate = sails_rs::scale_codec)]
+pub struct ExpenseV0 {
+    pub id: u32,
+    pub description: String,
+    pub amount: u128,
+    pub currency: String,
+    pub actor_id: ActorId,
+}
+
+#[derive(Encode, Decode)]
+#[codec(crate = sails_rs::scale_codec)]
+pub struct PaymentV0 {
+    pub id: u32,
+    pub from: ActorId,
+    pub to: ActorId,
+    pub amount: u32,
+}
+
+impl StateV0 {
+    // Method to migrate the first layout. The creator of every group (its
+    // first member) becomes its admin, expenses are split between all the
+    // members and payments must be confirmed again by their recipients
+    pub fn migrate(self, admin: ActorId) -> State {
+        let mut last_id = 0;
+
+        let groups = self.groups
+            .into_iter()
+            .map(|group| {
+                last_id = last_id.max(group.id);
+
+                let expenses = group.expenses
+                    .into_iter()
+                    .map(|expense| {
+                        last_id = last_id.max(expense.id);
+
+                        Expense {
+                            id: expense.id,
+                            description: expense.description,
+                            amount: expense.amount,
+                            currency: expense.currency,
+                            actor_id: expense.actor_id,
+                            participants: group.members.clone(),
+                            ..Default::default()
+                        }
+                    })
+                    .collect();
+
+                let payments = group.payments
+                    .into_iter()
+                    .map(|payment| {
+                        last_id = last_id.max(payment.id);
+
+                        Payment::new(payment.id, payment.from, payment.to, payment.amount)
+                    })
+                    .collect();
+
+                Group {
+                    id: group.id,
+                    name: group.name,
+                    admins: group.members.first().copied().into_iter().collect(),
+                    members: group.members,
+                    expenses,
+                    payments,
+                    ..Default::default()
+                }
+            })
+            .collect();
+
+        State {
+            groups,
+            ids: IdCounter::new(last_id),
+            ..State::new(Config {
+                admin,
+                ..Default::default()
+            })
+        }
+    }
+}
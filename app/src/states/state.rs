@@ -3,14 +3,103 @@
 use sails_rs::{
     prelude::*,
 };
-
-// Static mut variable (contract's state)
-pub static mut STATE: Option<State> = None;
+use core::cmp::Reverse;
 
 // Create a struct for the state
-#[derive(Clone, Default)]
+#[derive(Encode, Decode, Clone, Default)]
+#[codec(crate = sails_rs::scale_codec)]
 pub struct State {
     pub groups: Vec<Group>,
+    pub withdrawals: Vec<WithdrawalProposal>,
+    pub archived_groups: Vec<ArchivedGroup>,
+    pub config: Config,
+    // When set, the commands that change the state are rejected
+    pub paused: bool,
+    pub ids: IdCounter,
+}
+
+// Struct with the counter of the ids of the program, groups, expenses,
+// payments and every other entity share it so ids are unique in the program
+#[derive(Encode, Decode, Clone, Default)]
+#[codec(crate = sails_rs::scale_codec)]
+pub struct IdCounter {
+    last_id: u32,
+}
+
+impl IdCounter {
+    // Related function to continue the ids after the last one generated
+    pub fn new(last_id: u32) -> Self {
+        Self { last_id }
+    }
+
+    // Method to generate a new id
+    pub fn next_id(&mut self) -> u32 {
+        self.last_id += 1;
+        self.last_id
+    }
+}
+
+// Struct with the parameters of a deployed instance of the program
+#[derive(Encode, Decode, TypeInfo, Clone, Default)]
+#[codec(crate = sails_rs::scale_codec)]
+#[scale_info(crate = sails_rs::scale_info)]
+pub struct Config {
+    // Account that controls the program
+    pub admin: ActorId,
+    // Currencies accepted in expenses and budgets, empty to accept any currency
+    pub allowed_currencies: Vec<String>,
+    pub limits: Limits,
+    pub fees: FeeSettings,
+}
+
+// Struct with the fee that the program takes from treasury deposits
+#[derive(Encode, Decode, TypeInfo, Clone, Default)]
+#[codec(crate = sails_rs::scale_codec)]
+#[scale_info(crate = sails_rs::scale_info)]
+pub struct FeeSettings {
+    // Fee in basis points (1/100 of a percent) of every deposit
+    pub fee_bps: u16,
+    pub fee_collector: ActorId,
+}
+
+// Struct with the limits on the size of the data sent by users, to bound the
+// storage that a group can use
+#[derive(Encode, Decode, TypeInfo, Clone)]
+#[codec(crate = sails_rs::scale_codec)]
+#[scale_info(crate = sails_rs::scale_info)]
+pub struct Limits {
+    // Max length of group names, category names and tags
+    pub max_name_length: u32,
+    // Max length of descriptions and dispute reasons
+    pub max_description_length: u32,
+    pub max_currency_length: u32,
+    pub max_uri_length: u32,
+    pub max_tags: u32,
+    pub max_members: u32,
+    pub max_expenses: u32,
+    pub max_payments: u32,
+    // Max disputes, budgets and recurring expenses of a group
+    pub max_disputes: u32,
+    pub max_budgets: u32,
+    pub max_recurring_expenses: u32,
+}
+
+impl Default for Limits {
+    fn default() -> Self {
+        Limits {
+            max_name_length: 64,
+            max_description_length: 256,
+            max_currency_length: 8,
+            max_uri_length: 256,
+            max_tags: 8,
+            max_members: 100,
+            max_expenses: 1000,
+            max_payments: 1000,
+            max_disputes: 100,
+            max_budgets: 20,
+            max_recurring_expenses: 20,
+        }
+    }
 }
 
 // Struct to represent a group
@@ -20,9 +109,199 @@ pub struct State {
 pub struct Group {
     pub id: u32,
     pub name: String,
+    pub description: String,
+    pub avatar_uri: String,
+    pub avatar_hash: Option<H256>,
+    pub default_split_mode: SplitMode,
+    pub default_currency: String,
+    pub admins: Vec<ActorId>,
     pub members: Vec<ActorId>,
     pub expenses: Vec<Expense>,
-    pub payments: Vec<Payment>
+    pub payments: Vec<Payment>,
+    pub recurring_expenses: Vec<RecurringExpense>,
+    pub budgets: Vec<Budget>,
+    pub approval_policy: ApprovalPolicy,
+    pub disputes: Vec<Dispute>,
+    // Native value deposited by each member in the group treasury
+    pub pool: Vec<PoolBalance>,
+    pub pool_payments: Vec<PoolPayment>,
+    // Admin approvals needed to withdraw funds from the treasury, 0 means a
+    // majority of the admins
+    pub withdrawal_quorum: u32
+}
+
+// Struct with the summary that is kept of a closed group
+#[derive(Encode, Decode, TypeInfo, Clone, Default)]
+#[codec(crate = sails_rs::scale_codec)]
+#[scale_info(crate = sails_rs::scale_info)]
+pub struct ArchivedGroup {
+    pub id: u32,
+    pub name: String,
+    pub members: Vec<ActorId>,
+    pub closed_at: u32,
+    pub expense_count: u32,
+    pub payment_count: u32,
+    pub totals: Vec<CategoryTotal>,
+    // Balances that were not settled when the group was closed
+    pub final_balances: Vec<Balance>,
+    pub settlement: Vec<Transfer>,
+}
+
+impl ArchivedGroup {
+    pub fn new(group: &Group, closed_at: u32) -> Self {
+        ArchivedGroup {
+            id: group.id,
+            name: group.name.clone(),
+            members: group.members.clone(),
+            closed_at,
+            expense_count: group.expenses.len() as u32,
+            payment_count: group.payments.len() as u32,
+            totals: group.category_totals(),
+            final_balances: group.balances().into_iter().filter(|b| b.amount != 0).collect(),
+            settlement: group.settlement_plan(),
+        }
+    }
+}
+
+// Struct to represent a transfer between members that settles their balances
+#[derive(Encode, Decode, TypeInfo, Clone)]
+#[codec(crate = sails_rs::scale_codec)]
+#[scale_info(crate = sails_rs::scale_info)]
+pub struct Transfer {
+    pub from: ActorId,
+    pub to: ActorId,
+    pub amount: u128,
+}
+
+// Struct to represent a withdrawal of funds from a group treasury that
+// needs the approval of several admins before being executed
+#[derive(Encode, Decode, TypeInfo, Clone, Default)]
+#[codec(crate = sails_rs::scale_codec)]
+#[scale_info(crate = sails_rs::scale_info)]
+pub struct WithdrawalProposal {
+    pub id: u32,
+    pub group_id: u32,
+    pub proposer: ActorId,
+    pub to: ActorId,
+    pub amount: u128,
+    pub approvals: Vec<ActorId>,
+    // Block height after which the proposal can no longer be executed
+    pub expires_at: u32,
+    pub executed: bool,
+    // Set for the vendor payments proposed with `PayFromPool`, they are kept
+    // in the pool payments of the group once executed
+    pub description: Option<String>,
+}
+
+// Struct with the funds of a member in the group treasury
+#[derive(Encode, Decode, TypeInfo, Clone)]
+#[codec(crate = sails_rs::scale_codec)]
+#[scale_info(crate = sails_rs::scale_info)]
+pub struct PoolBalance {
+    pub actor_id: ActorId,
+    pub amount: u128,
+}
+
+// Struct to represent a payment made to a vendor with the group treasury
+#[derive(Encode, Decode, TypeInfo, Clone)]
+#[codec(crate = sails_rs::scale_codec)]
+#[scale_info(crate = sails_rs::scale_info)]
+pub struct PoolPayment {
+    pub id: u32,
+    pub vendor: ActorId,
+    pub amount: u128,
+    pub description: String,
+    pub paid_by: ActorId,
+}
+
+// Struct with the rules to approve the expenses of a group
+#[derive(Encode, Decode, TypeInfo, Clone, Default)]
+#[codec(crate = sails_rs::scale_codec)]
+#[scale_info(crate = sails_rs::scale_info)]
+pub struct ApprovalPolicy {
+    // Expenses with an amount above the threshold need approvals,
+    // None means that every expense is approved when added
+    pub threshold: Option<u128>,
+    pub required_approvals: u32,
+}
+
+impl ApprovalPolicy {
+    // Method to check if an expense amount needs to be approved by the members
+    pub fn requires_approval(&self, amount: u128) -> bool {
+        self.threshold.is_some_and(|threshold| amount > threshold)
+    }
+}
+
+// Enum with the status of a dispute, an open dispute keeps its expense out of
+// the balances until an admin resolves it
+#[derive(Encode, Decode, TypeInfo, Clone, Default, PartialEq)]
+#[codec(crate = sails_rs::scale_codec)]
+#[scale_info(crate = sails_rs::scale_info)]
+pub enum DisputeStatus {
+    #[default]
+    Open,
+    Upheld,
+    Amended(u128),
+    Voided,
+}
+
+// Enum with the decisions an admin can take to resolve a dispute
+#[derive(Encode, Decode, TypeInfo, Clone)]
+#[codec(crate = sails_rs::scale_codec)]
+#[scale_info(crate = sails_rs::scale_info)]
+pub enum DisputeResolution {
+    // The expense is kept as it is
+    Uphold,
+    // The expense is kept with a new amount
+    Amend(u128),
+    // The expense no longer counts in the balances
+    Void,
+}
+
+// Struct to represent a dispute opened by a member over an expense
+#[derive(Encode, Decode, TypeInfo, Clone, Default)]
+#[codec(crate = sails_rs::scale_codec)]
+#[scale_info(crate = sails_rs::scale_info)]
+pub struct Dispute {
+    pub id: u32,
+    pub expense_id: u32,
+    pub opened_by: ActorId,
+    pub reason: String,
+    pub status: DisputeStatus,
+}
+
+// Struct to represent the balance of a member: positive when the member is
+// owed money and negative when the member owes money
+#[derive(Encode, Decode, TypeInfo, Clone)]
+#[codec(crate = sails_rs::scale_codec)]
+#[scale_info(crate = sails_rs::scale_info)]
+pub struct Balance {
+    pub actor_id: ActorId,
+    pub amount: i128,
+}
+
+// Enum with the way clients split new expenses of a group by default
+#[derive(Encode, Decode, TypeInfo, Clone, Default)]
+#[codec(crate = sails_rs::scale_codec)]
+#[scale_info(crate = sails_rs::scale_info)]
+pub enum SplitMode {
+    #[default]
+    Equal,
+    Percentage,
+    Exact,
+}
+
+// Struct with the editable information of a group
+#[derive(Encode, Decode, TypeInfo, Clone, Default)]
+#[codec(crate = sails_rs::scale_codec)]
+#[scale_info(crate = sails_rs::scale_info)]
+pub struct GroupMetadataDTO {
+    pub name: String,
+    pub description: String,
+    pub avatar_uri: String,
+    pub avatar_hash: Option<H256>,
+    pub default_split_mode: SplitMode,
+    pub default_currency: String,
 }
 
 #[derive(Encode, Decode, TypeInfo, Clone, Default)]
@@ -41,7 +320,11 @@ pub struct Payment{
     pub id: u32,
     pub from: ActorId,
     pub to: ActorId,
-    pub amount: u32
+    pub amount: u32,
+    pub status: PaymentStatus,
+    // Block where the payment was added and its timestamp (in milliseconds)
+    pub created_block: u32,
+    pub created_at: u64,
 }
 
 impl Payment{
@@ -50,11 +333,56 @@ impl Payment{
             id,
             from,
             to,
-            amount
+            amount,
+            status: PaymentStatus::Unconfirmed,
+            ..Default::default()
         }
     }
 }
 
+// Enum with the status of a payment, only the payments confirmed by the
+// recipient count in the balances
+#[derive(Encode, Decode, TypeInfo, Clone, Default, PartialEq)]
+#[codec(crate = sails_rs::scale_codec)]
+#[scale_info(crate = sails_rs::scale_info)]
+pub enum PaymentStatus {
+    #[default]
+    Unconfirmed,
+    Confirmed,
+    Rejected,
+}
+
+// Enum to classify an expense for reports
+#[derive(Encode, Decode, TypeInfo, Clone, PartialEq)]
+#[codec(crate = sails_rs::scale_codec)]
+#[scale_info(crate = sails_rs::scale_info)]
+pub enum ExpenseCategory {
+    Food,
+    Transport,
+    Lodging,
+    Utilities,
+    Entertainment,
+    Other(String),
+}
+
+impl Default for ExpenseCategory {
+    fn default() -> Self {
+        ExpenseCategory::Other(String::new())
+    }
+}
+
+// Enum with the approval status of an expense
+#[derive(Encode, Decode, TypeInfo, Clone, Default, PartialEq)]
+#[codec(crate = sails_rs::scale_codec)]
+#[scale_info(crate = sails_rs::scale_info)]
+pub enum ExpenseStatus {
+    #[default]
+    Approved,
+    Pending,
+    Rejected,
+    Voided,
+}
+
 // Struct to represent an expense
 #[derive(Encode, Decode, TypeInfo, Clone, Default)]
 #[codec(crate = sails_rs::scale_codec)]
@@ -64,19 +392,43 @@ pub struct Expense {
     pub description: String,
     pub amount: u128,
     pub currency: String,
-    pub actor_id: ActorId
+    pub category: ExpenseCategory,
+    pub tags: Vec<String>,
+    pub actor_id: ActorId,
+    // Members that split the expense in equal parts
+    pub participants: Vec<ActorId>,
+    pub status: ExpenseStatus,
+    pub approvals: Vec<ActorId>,
+    // Block where the expense was added and its timestamp (in milliseconds)
+    pub created_block: u32,
+    pub created_at: u64,
 }
 
 impl Expense {
-    pub fn new(id: u32, description: String, amount: u128, currency: String, actor_id:ActorId) -> Self {
+    pub fn new(id: u32, expense_dto: ExpenseDTO, actor_id:ActorId) -> Self {
         Expense {
             id,
-            description,
-            amount,
-            currency,
-            actor_id
+            description: expense_dto.description,
+            amount: expense_dto.amount,
+            currency: expense_dto.currency,
+            category: expense_dto.category,
+            tags: expense_dto.tags,
+            actor_id,
+            participants: expense_dto.participants,
+            ..Default::default()
         }
     }
+
+    // Method to check if the expense is taken into account in the balances
+    pub fn counts_in_balances(&self) -> bool {
+        self.status == ExpenseStatus::Approved
+    }
+
+    // Method to split the amount between the participants, the remainder of
+    // the division is assigned to the first participants
+    pub fn shares(&self) -> Vec<(ActorId, u128)> {
+        math::split_equally(self.amount, &self.participants)
+    }
 }
 
 #[derive(Encode, Decode, TypeInfo, Clone, Default)]
@@ -86,34 +438,400 @@ pub struct ExpenseDTO {
     pub description: String,
     pub amount: u128,
     pub currency: String,
+    pub category: ExpenseCategory,
+    pub tags: Vec<String>,
+    // Members that split the expense, empty to split it between all members
+    pub participants: Vec<ActorId>,
 }
 
-// Impl to set methods or related functions
-impl State {
-    // Method to create a new instance
-    pub fn new() -> Self {
-        Self { ..Default::default() }
+// Struct to represent an expense that is added again every period
+#[derive(Encode, Decode, TypeInfo, Clone, Default)]
+#[codec(crate = sails_rs::scale_codec)]
+#[scale_info(crate = sails_rs::scale_info)]
+pub struct RecurringExpense {
+    pub id: u32,
+    pub creator: ActorId,
+    pub expense: ExpenseDTO,
+    pub period_blocks: u32,
+    // Occurrences that are still going to be added
+    pub remaining: u32,
+    pub paused: bool,
+    // Block height where the next occurrence is expected
+    pub next_block: u32,
+    // Changed on pause/resume so stale delayed messages are ignored
+    pub nonce: u32,
+}
+
+// Struct to represent a spending budget of a group for a period of blocks
+#[derive(Encode, Decode, TypeInfo, Clone, Default)]
+#[codec(crate = sails_rs::scale_codec)]
+#[scale_info(crate = sails_rs::scale_info)]
+pub struct Budget {
+    // None means the budget covers every category
+    pub category: Option<ExpenseCategory>,
+    pub currency: String,
+    pub limit: u128,
+    pub period_blocks: u32,
+    pub period_start: u32,
+    pub spent: u128,
+    // Percentages of the limit that trigger a threshold event
+    pub thresholds: Vec<u8>,
+    // Thresholds already reached in the current period
+    pub reached: Vec<u8>,
+    pub exceeded: bool,
+    // Expenses counted in the current period with the amount they added
+    pub counted: Vec<(u32, u128)>,
+}
+
+impl Budget {
+    // Method to check if an expense counts against the budget
+    pub fn applies_to(&self, expense: &Expense) -> bool {
+        self.currency == expense.currency
+            && self.category.as_ref().is_none_or(|c| *c == expense.category)
+    }
+
+    // Method to move the budget to the period that contains the block `now`
+    pub fn roll_period(&mut self, now: u32) {
+        if now >= self.period_start.saturating_add(self.period_blocks) {
+            let elapsed_periods = (now - self.period_start) / self.period_blocks;
+            self.period_start += elapsed_periods * self.period_blocks;
+            self.spent = 0;
+            self.reached.clear();
+            self.exceeded = false;
+            self.counted.clear();
+        }
+    }
+
+    // Method to add the spending of an expense to the budget, returns the
+    // thresholds reached for the first time in the period and whether the
+    // limit was just exceeded
+    pub fn record(&mut self, expense_id: u32, amount: u128, now: u32) -> (Vec<u8>, bool) {
+        self.roll_period(now);
+        self.counted.push((expense_id, amount));
+        self.spent = self.spent.saturating_add(amount);
+
+        self.check_limits()
+    }
+
+    // Method to change the spending of an expense already counted in the
+    // current period (0 removes it), the thresholds and the limit that are no
+    // longer crossed can be reached again
+    pub fn revise(&mut self, expense_id: u32, amount: u128, now: u32) -> (Vec<u8>, bool) {
+        self.roll_period(now);
+
+        let Some(entry) = self.counted.iter_mut().find(|(id, _)| *id == expense_id) else {
+            return (Vec::new(), false);
+        };
+        self.spent = self.spent.saturating_sub(entry.1).saturating_add(amount);
+        entry.1 = amount;
+        self.counted.retain(|(_, amount)| *amount > 0);
+
+        let spent = self.spent.saturating_mul(100);
+        let limit = self.limit;
+        self.reached.retain(|threshold| spent >= limit.saturating_mul(*threshold as u128));
+        self.exceeded = self.exceeded && self.spent > self.limit;
+
+        self.check_limits()
+    }
+
+    // Method to get the thresholds reached for the first time in the period
+    // and whether the limit was just exceeded
+    fn check_limits(&mut self) -> (Vec<u8>, bool) {
+        let mut reached = Vec::new();
+        for threshold in self.thresholds.iter() {
+            if !self.reached.contains(threshold)
+                && self.spent.saturating_mul(100) >= self.limit.saturating_mul(*threshold as u128)
+            {
+                reached.push(*threshold);
+            }
+        }
+        self.reached.extend(reached.iter());
+
+        let exceeded = !self.exceeded && self.spent > self.limit;
+        if exceeded {
+            self.exceeded = true;
+        }
+
+        (reached, exceeded)
+    }
+
+    // Method to get the status of the budget at the block `now`
+    pub fn status(&self, now: u32) -> BudgetStatus {
+        let mut budget = self.clone();
+        budget.roll_period(now);
+
+        BudgetStatus {
+            category: budget.category,
+            currency: budget.currency,
+            limit: budget.limit,
+            spent: budget.spent,
+            period_start: budget.period_start,
+            period_end: budget.period_start.saturating_add(budget.period_blocks),
+            exceeded: budget.exceeded,
+        }
+    }
+}
+
+// Struct to show the spending of a budget in its current period
+#[derive(Encode, Decode, TypeInfo, Clone)]
+#[codec(crate = sails_rs::scale_codec)]
+#[scale_info(crate = sails_rs::scale_info)]
+pub struct BudgetStatus {
+    pub category: Option<ExpenseCategory>,
+    pub currency: String,
+    pub limit: u128,
+    pub spent: u128,
+    pub period_start: u32,
+    pub period_end: u32,
+    pub exceeded: bool,
+}
+
+// Struct with the amount spent in a category (per currency)
+#[derive(Encode, Decode, TypeInfo, Clone)]
+#[codec(crate = sails_rs::scale_codec)]
+#[scale_info(crate = sails_rs::scale_info)]
+pub struct CategoryTotal {
+    pub category: ExpenseCategory,
+    pub currency: String,
+    pub total: u128,
+}
+
+// Movement in the balance of a member, with the id of the expense or payment
+#[derive(Encode, Decode, TypeInfo, Clone)]
+#[codec(crate = sails_rs::scale_codec)]
+#[scale_info(crate = sails_rs::scale_info)]
+pub enum StatementEntry {
+    ExpensePaid(u32),
+    ExpenseShare(u32),
+    PaymentSent(u32),
+    PaymentReceived(u32),
+}
+
+// Line of a member statement, with the change in the balance of the member
+// and the balance after it
+#[derive(Encode, Decode, TypeInfo, Clone)]
+#[codec(crate = sails_rs::scale_codec)]
+#[scale_info(crate = sails_rs::scale_info)]
+pub struct StatementLine {
+    pub entry: StatementEntry,
+    pub amount: i128,
+    pub balance: i128,
+}
+
+// Struct with the movements that make up the balance of a member
+#[derive(Encode, Decode, TypeInfo, Clone)]
+#[codec(crate = sails_rs::scale_codec)]
+#[scale_info(crate = sails_rs::scale_info)]
+pub struct MemberStatement {
+    pub actor_id: ActorId,
+    pub lines: Vec<StatementLine>,
+    pub balance: i128,
+}
+
+impl Group {
+    // Method to compute the balance of every member from the approved
+    // expenses and the payments of the group
+    pub fn balances(&self) -> Vec<Balance> {
+        let expenses = self.expenses
+            .iter()
+            .filter(|e| e.counts_in_balances() && !self.is_disputed(e.id))
+            .map(|e| math::Movement::Expense { payer: e.actor_id, amount: e.amount, participants: &e.participants });
+        let payments = self.payments
+            .iter()
+            .filter(|p| p.status == PaymentStatus::Confirmed)
+            .map(|p| math::Movement::Payment { from: p.from, to: p.to, amount: p.amount as u128 });
+
+        math::balances(&self.members, expenses.chain(payments))
+            .into_iter()
+            .map(|(actor_id, amount)| Balance { actor_id, amount })
+            .collect()
+    }
+
+    // Method to get the statement of a member, with the same expenses and
+    // payments as `balances` in the order they were added (expenses and
+    // payments share the id counter of the program)
+    pub fn member_statement(&self, actor_id: ActorId) -> MemberStatement {
+        let mut entries: Vec<(u32, StatementEntry, i128)> = Vec::new();
+
+        let expenses = self.expenses
+            .iter()
+            .filter(|e| e.counts_in_balances() && !self.is_disputed(e.id));
+
+        for expense in expenses {
+            if expense.actor_id == actor_id {
+                entries.push((expense.id, StatementEntry::ExpensePaid(expense.id), expense.amount as i128));
+            }
+
+            if let Some((_, share)) = expense.shares().into_iter().find(|(p, _)| *p == actor_id) {
+                entries.push((expense.id, StatementEntry::ExpenseShare(expense.id), -(share as i128)));
+            }
+        }
+
+        for payment in self.payments.iter().filter(|p| p.status == PaymentStatus::Confirmed) {
+            if payment.from == actor_id {
+                entries.push((payment.id, StatementEntry::PaymentSent(payment.id), payment.amount as i128));
+            }
+            if payment.to == actor_id {
+                entries.push((payment.id, StatementEntry::PaymentReceived(payment.id), -(payment.amount as i128)));
+            }
+        }
+
+        // Stable sort, the share of the payer of an expense stays after the amount paid
+        entries.sort_by_key(|(id, _, _)| *id);
+
+        let mut balance = 0;
+        let lines = entries
+            .into_iter()
+            .map(|(_, entry, amount)| {
+                balance += amount;
+                StatementLine { entry, amount, balance }
+            })
+            .collect();
+
+        MemberStatement { actor_id, lines, balance }
+    }
+
+    // Method to get the currency of the group, the default currency or else
+    // the currency of its first expense. Every expense of the group is in this
+    // currency, payments have no currency and are in the currency of the group
+    pub fn currency(&self) -> Option<&str> {
+        if !self.default_currency.is_empty() {
+            return Some(&self.default_currency);
+        }
+        self.expenses
+            .first()
+            .map(|e| e.currency.as_str())
+            .or_else(|| self.recurring_expenses.first().map(|r| r.expense.currency.as_str()))
+    }
+
+    // Method to sum the amounts of every expense and payment of the group,
+    // while the sum fits in an i128 no balance of the group can overflow
+    pub fn ledger_total(&self) -> u128 {
+        let expenses = self.expenses
+            .iter()
+            .fold(0u128, |total, e| total.saturating_add(e.amount));
+
+        self.payments
+            .iter()
+            .fold(expenses, |total, p| total.saturating_add(p.amount as u128))
     }
 
-    // Related function to init the state
-    pub fn init_state() {
-        unsafe {
-            STATE = Some(Self::new());
+    // Method to get the admin approvals needed to withdraw from the treasury,
+    // a majority of the admins until a quorum is set
+    pub fn required_withdrawal_approvals(&self) -> usize {
+        match self.withdrawal_quorum {
+            0 => self.admins.len() / 2 + 1,
+            quorum => quorum as usize,
         }
     }
 
-    // Related function to get the state as mutable
-    pub fn state_mut() -> &'static mut State {
-        let state = unsafe { STATE.as_mut() };
-        debug_assert!(state.is_some(), "The state is not initialized");
-        unsafe { state.unwrap_unchecked() }
+    // Method to get the funds of the group treasury
+    pub fn pool_total(&self) -> u128 {
+        self.pool.iter().map(|p| p.amount).sum()
     }
 
-    // Related function to get the state as reference
-    pub fn state_ref() -> &'static State {
-        let state = unsafe { STATE.as_ref() };
-        debug_assert!(state.is_some(), "The state is not initialized");
-        unsafe { state.unwrap_unchecked() }
+    // Method to add the funds deposited by a member to the group treasury
+    pub fn deposit_to_pool(&mut self, actor_id: ActorId, amount: u128) {
+        match self.pool.iter_mut().find(|p| p.actor_id == actor_id) {
+            Some(balance) => balance.amount += amount,
+            None => self.pool.push(PoolBalance { actor_id, amount }),
+        }
+    }
+
+    // Method to take an amount from the group treasury, every member pays a
+    // part proportional to the funds they deposited. The amount must not be
+    // greater than the funds of the treasury
+    pub fn debit_pool(&mut self, amount: u128) {
+        let mut funds: Vec<&mut u128> = self.pool.iter_mut().map(|p| &mut p.amount).collect();
+        math::debit_pro_rata(&mut funds, amount);
+
+        self.pool.retain(|p| p.amount > 0);
+    }
+
+    // Method to check if an expense has an open dispute
+    pub fn is_disputed(&self, expense_id: u32) -> bool {
+        self.disputes
+            .iter()
+            .any(|d| d.expense_id == expense_id && d.status == DisputeStatus::Open)
+    }
+
+    // Method to get the transfers that bring every balance to zero, the
+    // biggest debts are matched with the biggest credits first
+    pub fn settlement_plan(&self) -> Vec<Transfer> {
+        let balances = self.balances();
+
+        let mut debtors: Vec<(ActorId, u128)> = balances
+            .iter()
+            .filter(|b| b.amount < 0)
+            .map(|b| (b.actor_id, b.amount.unsigned_abs()))
+            .collect();
+        let mut creditors: Vec<(ActorId, u128)> = balances
+            .iter()
+            .filter(|b| b.amount > 0)
+            .map(|b| (b.actor_id, b.amount as u128))
+            .collect();
+
+        debtors.sort_by_key(|d| Reverse(d.1));
+        creditors.sort_by_key(|c| Reverse(c.1));
+
+        let mut transfers = Vec::new();
+        let (mut d, mut c) = (0, 0);
+
+        while d < debtors.len() && c < creditors.len() {
+            let amount = debtors[d].1.min(creditors[c].1);
+
+            transfers.push(Transfer {
+                from: debtors[d].0,
+                to: creditors[c].0,
+                amount,
+            });
+
+            debtors[d].1 -= amount;
+            creditors[c].1 -= amount;
+
+            if debtors[d].1 == 0 {
+                d += 1;
+            }
+            if creditors[c].1 == 0 {
+                c += 1;
+            }
+        }
+
+        transfers
+    }
+
+    // Method to sum the group expenses by category and currency, with the same
+    // expenses that count in the balances
+    pub fn category_totals(&self) -> Vec<CategoryTotal> {
+        let mut totals: Vec<CategoryTotal> = Vec::new();
+
+        let expenses = self.expenses
+            .iter()
+            .filter(|e| e.counts_in_balances() && !self.is_disputed(e.id));
+
+        for expense in expenses {
+            match totals
+                .iter_mut()
+                .find(|t| t.category == expense.category && t.currency == expense.currency)
+            {
+                Some(total) => total.total = total.total.saturating_add(expense.amount),
+                None => totals.push(CategoryTotal {
+                    category: expense.category.clone(),
+                    currency: expense.currency.clone(),
+                    total: expense.amount,
+                }),
+            }
+        }
+
+        totals
+    }
+}
+
+// Impl to set methods or related functions
+impl State {
+    // Method to create a new instance
+    pub fn new(config: Config) -> Self {
+        Self { config, ..Default::default() }
     }
 
     // Service to create a group 
@@ -121,6 +839,7 @@ impl State {
         self.groups.push(Group {
             id: _group_id,
             name: name,
+            admins: vec![wallet],
             members: vec![wallet],
             ..Default::default()
         });
@@ -133,6 +852,18 @@ impl State {
         }
     }
 
+    // Service to change the information of a group
+    pub fn update_group_metadata(&mut self, _group_id: u32, _metadata: GroupMetadataDTO) {
+        if let Some(group) = self.groups.iter_mut().find(|g| g.id == _group_id) {
+            group.name = _metadata.name;
+            group.description = _metadata.description;
+            group.avatar_uri = _metadata.avatar_uri;
+            group.avatar_hash = _metadata.avatar_hash;
+            group.default_split_mode = _metadata.default_split_mode;
+            group.default_currency = _metadata.default_currency;
+        }
+    }
+
     // Service to add an expense to the group
     pub fn add_expense(&mut self, _group_id: u32, _expense: Expense) {
         if let Some(group) = self.groups.iter_mut().find(|g| g.id == _group_id) {
@@ -155,6 +886,16 @@ impl State {
             group.expenses.retain(|e| e.id != _expense_id);
         }
     }
+
+    // Service to move a group to the archived groups, its pending withdrawal
+    // proposals are dropped
+    pub fn close_group(&mut self, _group_id: u32, _closed_at: u32) {
+        if let Some(index) = self.groups.iter().position(|g| g.id == _group_id) {
+            let group = self.groups.remove(index);
+            self.archived_groups.push(ArchivedGroup::new(&group, _closed_at));
+            self.withdrawals.retain(|w| w.group_id != _group_id || w.executed);
+        }
+    }
 }
 
 // Create a struct that can be sent to the user who reads state
@@ -163,11 +904,13 @@ impl State {
 #[scale_info(crate = sails_rs::scale_info)]
 pub struct IoState {
     pub groups: Vec<Group>,
+    pub withdrawals: Vec<WithdrawalProposal>,
+    pub archived_groups: Vec<ArchivedGroup>,
 }
 
 impl From<State> for IoState {
     fn from(value: State) -> Self {
-        let State { groups } = value;
-        Self { groups }
+        let State { groups, withdrawals, archived_groups, .. } = value;
+        Self { groups, withdrawals, archived_groups }
     }
 }
\ No newline at end of file
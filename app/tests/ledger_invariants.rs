@@ -0,0 +1,162 @@
+// Properties of the balances and the settlement plan, checked over the native
+// `State` without deploying the program
+use app::states::state::*;
+use proptest::prelude::*;
+use sails_rs::ActorId;
+
+const GROUP_ID: u32 = 1;
+
+#[derive(Debug, Clone)]
+struct ExpenseInput {
+    payer: usize,
+    amount: u128,
+    // Bit `i` set means that member `i` participates, empty means all members
+    participants: u8,
+}
+
+#[derive(Debug, Clone)]
+struct PaymentInput {
+    from: usize,
+    to: usize,
+    amount: u32,
+    confirmed: bool,
+}
+
+#[derive(Debug, Clone)]
+struct Ledger {
+    members: usize,
+    expenses: Vec<ExpenseInput>,
+    payments: Vec<PaymentInput>,
+}
+
+fn ledger() -> impl Strategy<Value = Ledger> {
+    (2usize..7).prop_flat_map(|members| {
+        let expense = (0..members, 1u128..1_000_000, any::<u8>())
+            .prop_map(|(payer, amount, participants)| ExpenseInput { payer, amount, participants });
+        let payment = (0..members, 0..members, 1u32..1_000_000, any::<bool>())
+            .prop_map(|(from, to, amount, confirmed)| PaymentInput { from, to, amount, confirmed });
+
+        (
+            Just(members),
+            prop::collection::vec(expense, 0..20),
+            prop::collection::vec(payment, 0..10),
+        )
+            .prop_map(|(members, expenses, payments)| Ledger { members, expenses, payments })
+    })
+}
+
+fn member(index: usize) -> ActorId {
+    ActorId::from(index as u64 + 1)
+}
+
+// Builds a state with one group holding the ledger, expenses and payments get
+// consecutive ids after the group
+fn build_state(ledger: &Ledger) -> State {
+    let mut state = State::new(Config::default());
+    let mut next_id = GROUP_ID + 1;
+
+    state.create_group(GROUP_ID, "Group".into(), member(0));
+    for index in 1..ledger.members {
+        state.join_group(GROUP_ID, member(index));
+    }
+
+    for input in ledger.expenses.iter() {
+        let mut participants: Vec<ActorId> = (0..ledger.members)
+            .filter(|index| input.participants & (1 << index) != 0)
+            .map(member)
+            .collect();
+        if participants.is_empty() {
+            participants = (0..ledger.members).map(member).collect();
+        }
+
+        let expense = Expense::new(
+            next_id,
+            ExpenseDTO {
+                description: "Expense".into(),
+                amount: input.amount,
+                currency: "VARA".into(),
+                participants,
+                ..Default::default()
+            },
+            member(input.payer),
+        );
+        next_id += 1;
+
+        state.add_expense(GROUP_ID, expense);
+    }
+
+    let group = group_mut(&mut state);
+    for input in ledger.payments.iter() {
+        let mut payment = Payment::new(next_id, member(input.from), member(input.to), input.amount);
+        if input.confirmed {
+            payment.status = PaymentStatus::Confirmed;
+        }
+        next_id += 1;
+
+        group.payments.push(payment);
+    }
+
+    state
+}
+
+fn group(state: &State) -> &Group {
+    state.groups.iter().find(|g| g.id == GROUP_ID).unwrap()
+}
+
+fn group_mut(state: &mut State) -> &mut Group {
+    state.groups.iter_mut().find(|g| g.id == GROUP_ID).unwrap()
+}
+
+proptest! {
+    #[test]
+    fn balances_sum_to_zero(ledger in ledger()) {
+        let state = build_state(&ledger);
+        let balances = group(&state).balances();
+
+        prop_assert_eq!(balances.len(), ledger.members);
+        prop_assert_eq!(balances.iter().map(|b| b.amount).sum::<i128>(), 0);
+    }
+
+    #[test]
+    fn settlement_plan_zeroes_balances(ledger in ledger()) {
+        let mut state = build_state(&ledger);
+        let plan = group(&state).settlement_plan();
+
+        // A plan never needs more transfers than members minus one
+        prop_assert!(plan.len() < ledger.members);
+
+        let group = group_mut(&mut state);
+        for (index, transfer) in plan.iter().enumerate() {
+            prop_assert!(transfer.amount > 0);
+            prop_assert_ne!(transfer.from, transfer.to);
+
+            let mut payment = Payment::new(u32::MAX - index as u32, transfer.from, transfer.to, transfer.amount as u32);
+            payment.status = PaymentStatus::Confirmed;
+            group.payments.push(payment);
+        }
+
+        prop_assert!(group.balances().iter().all(|b| b.amount == 0));
+        prop_assert!(group.settlement_plan().is_empty());
+    }
+
+    #[test]
+    fn deleting_and_re_adding_an_expense_keeps_balances(ledger in ledger(), pick in any::<prop::sample::Index>()) {
+        prop_assume!(!ledger.expenses.is_empty());
+
+        let mut state = build_state(&ledger);
+        let before = group(&state).balances();
+
+        let expense = pick.get(&group(&state).expenses).clone();
+
+        state.delete_expense(GROUP_ID, expense.id);
+        prop_assert!(group(&state).expenses.iter().all(|e| e.id != expense.id));
+
+        state.add_expense(GROUP_ID, expense);
+        let after = group(&state).balances();
+
+        prop_assert_eq!(
+            before.iter().map(|b| (b.actor_id, b.amount)).collect::<Vec<_>>(),
+            after.iter().map(|b| (b.actor_id, b.amount)).collect::<Vec<_>>()
+        );
+    }
+}
@@ -0,0 +1,56 @@
+// Snapshots exported by previous releases must still be restored, the
+// fixtures are kept as they were exported
+use app::states::{snapshot::VersionedState, state::*};
+use sails_rs::{prelude::*, ActorId};
+
+fn snapshot(hex_fixture: &str) -> VersionedState {
+    let bytes = hex::decode(hex_fixture.trim()).expect("Invalid fixture");
+    VersionedState::decode(&mut bytes.as_slice()).expect("Invalid snapshot")
+}
+
+#[test]
+fn v0_snapshot_is_migrated() {
+    let alice = ActorId::from(1u64);
+    let bob = ActorId::from(2u64);
+    let admin = ActorId::from(9u64);
+
+    let state = snapshot(include_str!("fixtures/snapshot_v0.hex")).migrate(admin);
+
+    // New ids continue after the last id of the snapshot
+    assert_eq!(state.ids.clone().next_id(), 5);
+    assert_eq!(state.config.admin, admin);
+    assert!(!state.paused);
+
+    assert_eq!(state.groups.len(), 2);
+    let group = &state.groups[0];
+    assert_eq!(group.name, "Trip");
+    assert_eq!(group.members, vec![alice, bob]);
+    assert_eq!(group.admins, vec![alice]);
+    assert_eq!(state.groups[1].admins, vec![bob]);
+
+    let expense = &group.expenses[0];
+    assert_eq!((expense.id, expense.amount), (2, 300));
+    assert!(expense.status == ExpenseStatus::Approved);
+    assert_eq!(expense.participants, vec![alice, bob]);
+
+    // Payments are confirmed again by their recipients
+    let payment = &group.payments[0];
+    assert_eq!((payment.id, payment.from, payment.to, payment.amount), (3, bob, alice, 100));
+    assert!(payment.status == PaymentStatus::Unconfirmed);
+
+    let balances = group.balances();
+    assert_eq!(balances.iter().find(|b| b.actor_id == alice).map(|b| b.amount), Some(150));
+    assert_eq!(balances.iter().find(|b| b.actor_id == bob).map(|b| b.amount), Some(-150));
+}
+
+#[test]
+fn current_snapshot_round_trips() {
+    let state = snapshot(include_str!("fixtures/snapshot_v0.hex")).migrate(ActorId::zero());
+
+    let bytes = VersionedState::current(state.clone()).encode();
+    let restored = VersionedState::decode(&mut bytes.as_slice())
+        .expect("Invalid snapshot")
+        .migrate(ActorId::zero());
+
+    assert_eq!(restored.encode(), state.encode());
+}
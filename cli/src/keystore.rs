@@ -0,0 +1,103 @@
+// Encrypted keystore of the signer. The secret URI is encrypted with
+// xsalsa20-poly1305 under a key derived from a password with scrypt, the same
+// primitives as the polkadot.js keystore. It keeps the secret URI rather than
+// the key pair because the node client signs with a secret URI
+use crate::CliError;
+use crypto_secretbox::{
+    aead::{rand_core::RngCore, Aead, KeyInit, OsRng},
+    Nonce, XSalsa20Poly1305,
+};
+use serde::{Deserialize, Serialize};
+
+const VERSION: u32 = 1;
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct Keystore {
+    pub version: u32,
+    pub kdf: Kdf,
+    // Nonce of the cipher and encrypted secret URI, in hex
+    pub nonce: String,
+    pub ciphertext: String,
+}
+
+// Parameters of scrypt, the cost is 2^log_n
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct Kdf {
+    pub salt: String,
+    pub log_n: u8,
+    pub r: u32,
+    pub p: u32,
+}
+
+impl Kdf {
+    pub fn with_cost(log_n: u8) -> Self {
+        let mut salt = [0u8; 32];
+        OsRng.fill_bytes(&mut salt);
+
+        Self { salt: hex::encode(salt), log_n, r: 8, p: 1 }
+    }
+
+    fn cipher(&self, password: &str) -> Result<XSalsa20Poly1305, CliError> {
+        let salt = hex::decode(&self.salt).map_err(|_| invalid("salt"))?;
+        let params = scrypt::Params::new(self.log_n, self.r, self.p, 32).map_err(|_| invalid("kdf"))?;
+
+        let mut key = [0u8; 32];
+        scrypt::scrypt(password.as_bytes(), &salt, &params, &mut key).map_err(|_| invalid("kdf"))?;
+
+        Ok(XSalsa20Poly1305::new(&key.into()))
+    }
+}
+
+// Cost of the polkadot.js keystore
+impl Default for Kdf {
+    fn default() -> Self {
+        Self::with_cost(15)
+    }
+}
+
+impl Keystore {
+    pub fn encrypt(suri: &str, password: &str, kdf: Kdf) -> Result<Self, CliError> {
+        let mut nonce = [0u8; 24];
+        OsRng.fill_bytes(&mut nonce);
+
+        let ciphertext = kdf
+            .cipher(password)?
+            .encrypt(&Nonce::from(nonce), suri.as_bytes())
+            .map_err(|_| CliError::Keystore("Failed to encrypt the secret".to_owned()))?;
+
+        Ok(Self { version: VERSION, kdf, nonce: hex::encode(nonce), ciphertext: hex::encode(ciphertext) })
+    }
+
+    // Returns the secret URI, fails when the password is wrong
+    pub fn decrypt(&self, password: &str) -> Result<String, CliError> {
+        if self.version != VERSION {
+            return Err(CliError::Keystore(format!("Unsupported keystore version {}", self.version)));
+        }
+
+        let nonce: [u8; 24] = hex::decode(&self.nonce)
+            .ok()
+            .and_then(|nonce| nonce.try_into().ok())
+            .ok_or_else(|| invalid("nonce"))?;
+        let ciphertext = hex::decode(&self.ciphertext).map_err(|_| invalid("ciphertext"))?;
+
+        let suri = self
+            .kdf
+            .cipher(password)?
+            .decrypt(&Nonce::from(nonce), ciphertext.as_slice())
+            .map_err(|_| CliError::Keystore("Wrong password".to_owned()))?;
+
+        String::from_utf8(suri).map_err(|_| invalid("secret"))
+    }
+
+    pub fn from_json(json: &str) -> Result<Self, CliError> {
+        serde_json::from_str(json).map_err(|error| CliError::Keystore(format!("Invalid keystore: {error}")))
+    }
+
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(self).expect("Keystore is serializable")
+    }
+}
+
+fn invalid(field: &str) -> CliError {
+    CliError::Keystore(format!("Invalid keystore: bad {field}"))
+}
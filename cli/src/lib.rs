@@ -0,0 +1,306 @@
+// Commands of the group command line tool. They only depend on the remoting,
+// so the same code runs against a node (gclient) or in gtest
+pub mod keystore;
+
+use clap::Subcommand;
+use client::{traits::Service as _, Events, ExpenseCategory, ExpenseDto, Group, Service};
+use sails_rs::{calls::*, ActorId};
+use std::{fmt, path::PathBuf};
+
+pub use ledger::format_actor_id;
+
+#[derive(Subcommand, Debug, Clone)]
+pub enum Command {
+    /// Create a group, the sender is its first admin and member
+    CreateGroup {
+        name: String,
+    },
+    /// Join a group
+    Join {
+        group_id: u32,
+    },
+    /// Add an expense paid by the sender
+    AddExpense {
+        group_id: u32,
+        amount: u128,
+        #[arg(long)]
+        currency: String,
+        #[arg(long, default_value = "")]
+        description: String,
+        /// Food, transport, lodging, utilities, entertainment or any other name
+        #[arg(long, default_value = "other")]
+        category: String,
+        #[arg(long)]
+        tag: Vec<String>,
+        /// Members that share the expense, all the members when empty
+        #[arg(long, value_parser = parse_actor_id)]
+        participant: Vec<ActorId>,
+    },
+    /// Record a payment from the sender to another member
+    Pay {
+        group_id: u32,
+        #[arg(value_parser = parse_actor_id)]
+        to: ActorId,
+        amount: u32,
+    },
+    /// Show the balance of every member of a group
+    Balances {
+        group_id: u32,
+    },
+    /// Show the transfers that settle the balances of a group
+    SettlePlan {
+        group_id: u32,
+    },
+    /// Import a Splitwise CSV export, only the expenses paid by the sender are
+    /// sent since the program takes the sender as payer, the rows paid by
+    /// other members are reported so they can import them
+    ImportCsv {
+        group_id: u32,
+        file: PathBuf,
+        /// CSV with the `name` and `account` of every member of the export
+        #[arg(long)]
+        mapping: PathBuf,
+        #[arg(long, default_value_t = 2)]
+        decimals: u32,
+        #[arg(long, default_value_t = 50)]
+        batch_size: usize,
+        /// Batches already imported by a previous run, they are not sent again
+        #[arg(long, default_value_t = 0)]
+        skip_batches: usize,
+    },
+    /// Export the ledger of a group as CSV with the running balances
+    ExportCsv {
+        group_id: u32,
+        /// File to write, the CSV is printed when not set
+        #[arg(long)]
+        output: Option<PathBuf>,
+    },
+    /// Export the state of the program, or of one group, as JSON
+    ExportJson {
+        /// Group to export, the whole state is exported when not set
+        #[arg(long)]
+        group: Option<u32>,
+        /// File to write, the JSON is printed when not set
+        #[arg(long)]
+        output: Option<PathBuf>,
+    },
+}
+
+#[derive(Debug)]
+pub enum CliError {
+    // The message could not be sent or its reply could not be decoded
+    Remoting(sails_rs::errors::Error),
+    // The program replied with an error
+    Rejected(Events),
+    GroupNotFound(u32),
+    Export(String),
+    Import(String),
+    Keystore(String),
+    // An import failed after some of its batches were added
+    PartialImport { applied: usize, error: Box<CliError> },
+}
+
+impl fmt::Display for CliError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CliError::Remoting(error) => write!(f, "Failed to reach the program: {error}"),
+            CliError::Rejected(Events::Error(message)) => write!(f, "Rejected: {message}"),
+            CliError::Rejected(event) => write!(f, "Rejected: {event:?}"),
+            CliError::GroupNotFound(group_id) => write!(f, "Group {group_id} not found"),
+            CliError::Export(error) => write!(f, "Failed to export the ledger: {error}"),
+            CliError::Import(error) => write!(f, "Failed to import the ledger: {error}"),
+            CliError::Keystore(error) => write!(f, "Failed to use the keystore: {error}"),
+            CliError::PartialImport { applied, error } => write!(
+                f,
+                "{error}\n{applied} batches were imported, run the import again with --skip-batches {applied} to resume"
+            ),
+        }
+    }
+}
+
+impl std::error::Error for CliError {}
+
+impl From<sails_rs::errors::Error> for CliError {
+    fn from(error: sails_rs::errors::Error) -> Self {
+        CliError::Remoting(error)
+    }
+}
+
+// Runs the command against the program as `signer`, the account that signs
+// the messages of the remoting, and returns the text to print
+pub async fn run<R: Remoting + Clone>(
+    remoting: R,
+    signer: ActorId,
+    program_id: ActorId,
+    command: Command,
+) -> Result<String, CliError> {
+    let mut service = Service::new(remoting);
+
+    let reply = match command {
+        Command::CreateGroup { name } => {
+            service.create_group(name).send_recv(program_id).await?
+        }
+        Command::Join { group_id } => {
+            service.join_group(group_id).send_recv(program_id).await?
+        }
+        Command::AddExpense { group_id, amount, currency, description, category, tag, participant } => {
+            let expense = ExpenseDto {
+                description,
+                amount,
+                currency,
+                category: parse_category(&category),
+                tags: tag,
+                participants: participant,
+            };
+
+            service.add_expense(group_id, expense).send_recv(program_id).await?
+        }
+        Command::Pay { group_id, to, amount } => {
+            service.add_payment(group_id, amount, to).send_recv(program_id).await?
+        }
+        Command::Balances { group_id } => {
+            let balances = service.query_balances(group_id)
+                .recv(program_id)
+                .await?
+                .ok_or(CliError::GroupNotFound(group_id))?;
+
+            return Ok(balances
+                .iter()
+                .map(|b| format!("{} {}", format_actor_id(&b.actor_id), b.amount))
+                .collect::<Vec<_>>()
+                .join("\n"));
+        }
+        Command::SettlePlan { group_id } => {
+            let plan = service.query_settlement_plan(group_id)
+                .recv(program_id)
+                .await?
+                .ok_or(CliError::GroupNotFound(group_id))?;
+
+            return Ok(plan
+                .iter()
+                .map(|t| format!("{} -> {} {}", format_actor_id(&t.from), format_actor_id(&t.to), t.amount))
+                .collect::<Vec<_>>()
+                .join("\n"));
+        }
+        Command::ImportCsv { group_id, file, mapping, decimals, batch_size, skip_batches } => {
+            let read = |path: &PathBuf| std::fs::File::open(path).map_err(|error| CliError::Import(error.to_string()));
+
+            let mapping = ledger::parse_mapping(read(&mapping)?)
+                .map_err(|error| CliError::Import(error.to_string()))?;
+            let import = ledger::import_splitwise_csv(read(&file)?, &mapping, &ledger::ImportOptions { decimals, batch_size })
+                .map_err(|error| CliError::Import(error.to_string()))?;
+
+            // The batches of the signer keep the order of the export, so a
+            // failed import is resumed by skipping the batches already added
+            let batches: Vec<_> = import
+                .batches(batch_size)
+                .into_iter()
+                .filter(|(payer, _)| *payer == signer)
+                .map(|(_, expenses)| expenses)
+                .collect();
+
+            let mut lines = Vec::new();
+            let mut imported = 0;
+
+            for (index, expenses) in batches.iter().enumerate().skip(skip_batches) {
+                let count = expenses.len();
+                let reply = service.add_expenses_batch(group_id, expenses.clone()).send_recv(program_id).await;
+
+                let error = match reply {
+                    Ok(Events::ExpensesBatchAdded(..)) => None,
+                    Ok(reply) => Some(CliError::Rejected(reply)),
+                    Err(error) => Some(CliError::Remoting(error)),
+                };
+                if let Some(error) = error {
+                    return Err(CliError::PartialImport { applied: index, error: Box::new(error) });
+                }
+
+                imported += count;
+                lines.push(format!("Batch {} of {} imported", index + 1, batches.len()));
+            }
+
+            let others: Vec<_> = import.expenses.iter().filter(|e| e.payer != signer).collect();
+
+            lines.push(format!("{imported} expenses imported to group {group_id}"));
+            if !others.is_empty() {
+                lines.push(format!("{} expenses paid by other members skipped, they must import them", others.len()));
+                lines.extend(others.iter().map(|e| format!("Line {} skipped: paid by {}", e.line, format_actor_id(&e.payer))));
+            }
+            lines.extend(import.skipped.iter().map(|row| format!("Line {} skipped: {}", row.line, row.reason)));
+
+            return Ok(lines.join("\n"));
+        }
+        Command::ExportCsv { group_id, output } => {
+            let group = query_group(&service, program_id, group_id).await?;
+
+            let csv = ledger::group_ledger_csv(&group)
+                .map_err(|error| CliError::Export(error.to_string()))?;
+
+            return match output {
+                Some(path) => {
+                    std::fs::write(&path, csv).map_err(|error| CliError::Export(error.to_string()))?;
+                    Ok(format!("Ledger of group {group_id} written to {}", path.display()))
+                }
+                None => Ok(csv),
+            };
+        }
+        Command::ExportJson { group, output } => {
+            let json = match group {
+                Some(group_id) => ledger::group_json(&query_group(&service, program_id, group_id).await?),
+                None => ledger::state_json(&service.query().recv(program_id).await?),
+            }
+            .map_err(|error| CliError::Export(error.to_string()))?;
+
+            return match output {
+                Some(path) => {
+                    std::fs::write(&path, json).map_err(|error| CliError::Export(error.to_string()))?;
+                    Ok(format!("State written to {}", path.display()))
+                }
+                None => Ok(json),
+            };
+        }
+    };
+
+    match reply {
+        Events::Error(_) | Events::LimitExceeded(_) => Err(CliError::Rejected(reply)),
+        Events::GroupCreated(group_id) => Ok(format!("Group {group_id} created")),
+        Events::UserJoined((_, group_id)) => Ok(format!("Joined group {group_id}")),
+        Events::ExpenseAdded((group_id, expense_id)) => Ok(format!("Expense {expense_id} added to group {group_id}")),
+        Events::PaymentAdded((group_id, amount)) => Ok(format!("Payment of {amount} added to group {group_id}")),
+        Events::ExpensesBatchAdded((group_id, count)) => Ok(format!("{count} expenses added to group {group_id}")),
+        event => Ok(format!("{event:?}")),
+    }
+}
+
+// Fetches a single group, its members are queried first since `QueryGroup`
+// panics in the program when the group does not exist
+async fn query_group<R: Remoting + Clone>(service: &Service<R>, program_id: ActorId, group_id: u32) -> Result<Group, CliError> {
+    if service.query_group_members(group_id).recv(program_id).await?.is_none() {
+        return Err(CliError::GroupNotFound(group_id));
+    }
+
+    Ok(service.query_group(group_id).recv(program_id).await?)
+}
+
+// Accounts are written as 32 bytes in hex, with or without the 0x prefix
+pub fn parse_actor_id(text: &str) -> Result<ActorId, String> {
+    let bytes = hex::decode(text.trim_start_matches("0x"))
+        .map_err(|error| format!("Invalid account {text}: {error}"))?;
+    let bytes: [u8; 32] = bytes
+        .try_into()
+        .map_err(|_| format!("Invalid account {text}: expected 32 bytes"))?;
+
+    Ok(ActorId::from(bytes))
+}
+
+pub fn parse_category(text: &str) -> ExpenseCategory {
+    match text.to_lowercase().as_str() {
+        "food" => ExpenseCategory::Food,
+        "transport" => ExpenseCategory::Transport,
+        "lodging" => ExpenseCategory::Lodging,
+        "utilities" => ExpenseCategory::Utilities,
+        "entertainment" => ExpenseCategory::Entertainment,
+        "other" => ExpenseCategory::Other(String::new()),
+        _ => ExpenseCategory::Other(text.to_owned()),
+    }
+}
@@ -0,0 +1,119 @@
+use clap::{Parser, Subcommand};
+use cli::{
+    keystore::{Kdf, Keystore},
+    parse_actor_id, run, CliError, Command,
+};
+use sails_rs::{
+    gclient::{calls::GClientRemoting, GearApi, WSAddress},
+    ActorId,
+};
+use std::{fs::OpenOptions, io::Write, path::PathBuf, process::ExitCode};
+
+/// Manage expense groups of a deployed program
+#[derive(Parser, Debug)]
+#[command(name = "group-cli", version)]
+struct Args {
+    /// Address of the node
+    #[arg(long, env = "GROUP_CLI_ENDPOINT", default_value = "ws://127.0.0.1")]
+    endpoint: String,
+
+    /// Port of the node
+    #[arg(long, env = "GROUP_CLI_PORT", default_value_t = 9944)]
+    port: u16,
+
+    /// Account of the program
+    #[arg(long, env = "GROUP_CLI_PROGRAM", value_parser = parse_actor_id)]
+    program: Option<ActorId>,
+
+    /// Encrypted keystore of the sender, written by `create-keystore`. Its
+    /// password is read from GROUP_CLI_PASSWORD or prompted. Without a
+    /// keystore the secret URI of the sender is read from GROUP_CLI_SURI
+    #[arg(long, env = "GROUP_CLI_KEYSTORE")]
+    keystore: Option<PathBuf>,
+
+    #[command(subcommand)]
+    action: Action,
+}
+
+#[derive(Subcommand, Debug)]
+enum Action {
+    /// Encrypt a secret URI into a keystore, the secret URI is prompted and
+    /// the password is read from GROUP_CLI_PASSWORD or prompted
+    CreateKeystore {
+        output: PathBuf,
+    },
+    #[command(flatten)]
+    Group(Command),
+}
+
+#[tokio::main]
+async fn main() -> ExitCode {
+    let args = Args::parse();
+
+    match execute(args).await {
+        Ok(output) => {
+            println!("{output}");
+            ExitCode::SUCCESS
+        }
+        Err(error) => {
+            eprintln!("{error}");
+            ExitCode::FAILURE
+        }
+    }
+}
+
+async fn execute(args: Args) -> Result<String, Box<dyn std::error::Error>> {
+    let command = match args.action {
+        Action::CreateKeystore { output } => return create_keystore(output),
+        Action::Group(command) => command,
+    };
+    let program = args.program.ok_or("No program set, use --program or GROUP_CLI_PROGRAM")?;
+
+    let suri = match args.keystore {
+        Some(keystore) => {
+            let keystore = Keystore::from_json(&std::fs::read_to_string(keystore)?)?;
+            keystore.decrypt(&password("Keystore password: ")?)?
+        }
+        None => std::env::var("GROUP_CLI_SURI")
+            .map_err(|_| "No signer configured, set --keystore or GROUP_CLI_SURI")?,
+    };
+
+    let api = GearApi::init_with(WSAddress::new(args.endpoint, args.port), suri).await?;
+    let signer: &[u8; 32] = api.account_id().as_ref();
+    let signer = ActorId::from(*signer);
+    let remoting = GClientRemoting::new(api);
+
+    Ok(run(remoting, signer, program, command).await?)
+}
+
+fn create_keystore(output: PathBuf) -> Result<String, Box<dyn std::error::Error>> {
+    let suri = rpassword::prompt_password("Secret URI: ")?;
+    let password = match std::env::var("GROUP_CLI_PASSWORD") {
+        Ok(password) => password,
+        Err(_) => {
+            let password = rpassword::prompt_password("New keystore password: ")?;
+            if rpassword::prompt_password("Repeat the password: ")? != password {
+                return Err(CliError::Keystore("The passwords do not match".to_owned()).into());
+            }
+            password
+        }
+    };
+
+    let keystore = Keystore::encrypt(&suri, &password, Kdf::default())?;
+
+    // Only the owner can read the keystore, an existing file is not replaced
+    let mut options = OpenOptions::new();
+    options.write(true).create_new(true);
+    #[cfg(unix)]
+    std::os::unix::fs::OpenOptionsExt::mode(&mut options, 0o600);
+    options.open(&output)?.write_all(keystore.to_json().as_bytes())?;
+
+    Ok(format!("Keystore written to {}", output.display()))
+}
+
+fn password(prompt: &str) -> std::io::Result<String> {
+    match std::env::var("GROUP_CLI_PASSWORD") {
+        Ok(password) => Ok(password),
+        Err(_) => rpassword::prompt_password(prompt),
+    }
+}
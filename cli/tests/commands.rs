@@ -0,0 +1,140 @@
+use cli::{format_actor_id, run, Command, CliError};
+use client::Events;
+use tests::{Fixture, ADMIN, ALICE, BOB};
+
+#[tokio::test]
+async fn commands_work() {
+    let fixture = Fixture::new().await;
+    let program_id = fixture.program_id;
+
+    let output = run(fixture.remoting_as(ADMIN), ADMIN.into(), program_id, Command::CreateGroup { name: "Trip".to_owned() }).await.unwrap();
+    let group_id: u32 = output
+        .strip_prefix("Group ")
+        .and_then(|rest| rest.strip_suffix(" created"))
+        .unwrap()
+        .parse()
+        .unwrap();
+
+    let output = run(fixture.remoting_as(ALICE), ALICE.into(), program_id, Command::Join { group_id }).await.unwrap();
+    assert_eq!(output, format!("Joined group {group_id}"));
+
+    run(fixture.remoting_as(ADMIN), ADMIN.into(), program_id, Command::AddExpense {
+        group_id,
+        amount: 100,
+        currency: "VARA".to_owned(),
+        description: "Taxi".to_owned(),
+        category: "transport".to_owned(),
+        tag: vec![],
+        participant: vec![],
+    }).await.unwrap();
+
+    let output = run(fixture.remoting_as(ALICE), ALICE.into(), program_id, Command::Balances { group_id }).await.unwrap();
+    assert_eq!(output, format!(
+        "{} 50\n{} -50",
+        format_actor_id(&ADMIN.into()),
+        format_actor_id(&ALICE.into())
+    ));
+
+    let output = run(fixture.remoting_as(ALICE), ALICE.into(), program_id, Command::SettlePlan { group_id }).await.unwrap();
+    assert_eq!(output, format!(
+        "{} -> {} 50",
+        format_actor_id(&ALICE.into()),
+        format_actor_id(&ADMIN.into())
+    ));
+
+    let output = run(fixture.remoting_as(ALICE), ALICE.into(), program_id, Command::Pay { group_id, to: ADMIN.into(), amount: 50 }).await.unwrap();
+    assert_eq!(output, format!("Payment of 50 added to group {group_id}"));
+
+    // Every row is dated with the block where its entry was added
+    let csv = run(fixture.remoting_as(ALICE), ALICE.into(), program_id, Command::ExportCsv { group_id, output: None }).await.unwrap();
+    let rows: Vec<&str> = csv.lines().skip(1).collect();
+    assert_eq!(rows.len(), 2);
+    assert!(rows.iter().all(|row| row.split(',').next().is_some_and(|date| date.len() == "2024-01-05".len())));
+}
+
+#[tokio::test]
+async fn commands_report_errors() {
+    let fixture = Fixture::new().await;
+    let program_id = fixture.program_id;
+
+    let error = run(fixture.remoting_as(BOB), BOB.into(), program_id, Command::Join { group_id: 7 }).await.unwrap_err();
+    assert!(matches!(error, CliError::Rejected(Events::Error(ref message)) if message == "Group not found"));
+
+    let error = run(fixture.remoting_as(BOB), BOB.into(), program_id, Command::Balances { group_id: 7 }).await.unwrap_err();
+    assert!(matches!(error, CliError::GroupNotFound(7)));
+
+    let error = run(fixture.remoting_as(BOB), BOB.into(), program_id, Command::ExportCsv { group_id: 7, output: None }).await.unwrap_err();
+    assert!(matches!(error, CliError::GroupNotFound(7)));
+
+    // Expenses and payments are only between members of the group
+    let output = run(fixture.remoting_as(ADMIN), ADMIN.into(), program_id, Command::CreateGroup { name: "Trip".to_owned() }).await.unwrap();
+    let group_id: u32 = output
+        .strip_prefix("Group ")
+        .and_then(|rest| rest.strip_suffix(" created"))
+        .unwrap()
+        .parse()
+        .unwrap();
+
+    let error = run(fixture.remoting_as(BOB), BOB.into(), program_id, Command::AddExpense {
+        group_id,
+        amount: 100,
+        currency: "VARA".to_owned(),
+        description: String::new(),
+        category: "other".to_owned(),
+        tag: vec![],
+        participant: vec![],
+    }).await.unwrap_err();
+    assert!(matches!(error, CliError::Rejected(Events::Error(ref message)) if message == "User is not a member of the group"));
+
+    let error = run(fixture.remoting_as(ADMIN), ADMIN.into(), program_id, Command::Pay { group_id, to: BOB.into(), amount: 10 }).await.unwrap_err();
+    assert!(matches!(error, CliError::Rejected(Events::Error(ref message)) if message == "Recipient is not a member of the group"));
+}
+
+#[tokio::test]
+async fn import_sends_the_expenses_of_the_signer() {
+    let fixture = Fixture::new().await;
+    let program_id = fixture.program_id;
+
+    let output = run(fixture.remoting_as(ADMIN), ADMIN.into(), program_id, Command::CreateGroup { name: "Trip".to_owned() }).await.unwrap();
+    let group_id: u32 = output.trim_start_matches("Group ").trim_end_matches(" created").parse().unwrap();
+    run(fixture.remoting_as(ALICE), ALICE.into(), program_id, Command::Join { group_id }).await.unwrap();
+
+    let dir = std::env::temp_dir().join(format!("group-cli-import-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let (file, mapping) = (dir.join("export.csv"), dir.join("mapping.csv"));
+
+    std::fs::write(&mapping, format!(
+        "name,account\nAdmin,{}\nAlice,{}\n",
+        format_actor_id(&ADMIN.into()),
+        format_actor_id(&ALICE.into())
+    )).unwrap();
+    std::fs::write(&file, "Date,Description,Category,Cost,Currency,Admin,Alice
+2024-01-01,Taxi,Taxi,10.00,VARA,5.00,-5.00
+2024-01-02,Lunch,Dining out,20.00,VARA,10.00,-10.00
+2024-01-03,Museum,General,8.00,VARA,-4.00,4.00
+").unwrap();
+
+    let import = |skip_batches| Command::ImportCsv {
+        group_id,
+        file: file.clone(),
+        mapping: mapping.clone(),
+        decimals: 2,
+        batch_size: 1,
+        skip_batches,
+    };
+
+    // The first batch is taken as imported by a previous run
+    let output = run(fixture.remoting_as(ADMIN), ADMIN.into(), program_id, import(1)).await.unwrap();
+    let lines: Vec<&str> = output.lines().collect();
+    assert_eq!(lines, vec![
+        "Batch 2 of 2 imported",
+        &format!("1 expenses imported to group {group_id}"),
+        "1 expenses paid by other members skipped, they must import them",
+        &format!("Line 4 skipped: paid by {}", format_actor_id(&ALICE.into())),
+    ]);
+
+    let output = run(fixture.remoting_as(ALICE), ALICE.into(), program_id, import(0)).await.unwrap();
+    assert!(output.starts_with(&format!("Batch 1 of 1 imported\n1 expenses imported to group {group_id}")));
+
+    std::fs::remove_dir_all(dir).unwrap();
+}
@@ -0,0 +1,47 @@
+use cli::{
+    keystore::{Kdf, Keystore},
+    CliError,
+};
+
+// Low cost so the tests do not spend seconds in scrypt
+fn kdf() -> Kdf {
+    Kdf::with_cost(4)
+}
+
+#[test]
+fn keystore_returns_the_secret_uri() {
+    let keystore = Keystore::encrypt("//Alice", "correct horse", kdf()).unwrap();
+    assert!(!keystore.to_json().contains("Alice"));
+
+    let restored = Keystore::from_json(&keystore.to_json()).unwrap();
+    assert_eq!(restored, keystore);
+    assert_eq!(restored.decrypt("correct horse").unwrap(), "//Alice");
+}
+
+#[test]
+fn keystore_errors() {
+    let keystore = Keystore::encrypt("//Alice", "correct horse", kdf()).unwrap();
+
+    let error = keystore.decrypt("wrong horse").unwrap_err();
+    assert!(matches!(error, CliError::Keystore(message) if message == "Wrong password"));
+
+    let mut tampered = keystore.clone();
+    tampered.ciphertext.replace_range(..2, if tampered.ciphertext.starts_with("00") { "01" } else { "00" });
+    assert!(matches!(tampered.decrypt("correct horse"), Err(CliError::Keystore(message)) if message == "Wrong password"));
+
+    let mut future = keystore.clone();
+    future.version = 2;
+    assert!(matches!(future.decrypt("correct horse"), Err(CliError::Keystore(message)) if message == "Unsupported keystore version 2"));
+
+    assert!(matches!(Keystore::from_json("{}"), Err(CliError::Keystore(_))));
+}
+
+#[test]
+fn every_keystore_has_its_own_salt_and_nonce() {
+    let first = Keystore::encrypt("//Alice", "correct horse", kdf()).unwrap();
+    let second = Keystore::encrypt("//Alice", "correct horse", kdf()).unwrap();
+
+    assert_ne!(first.kdf.salt, second.kdf.salt);
+    assert_ne!(first.nonce, second.nonce);
+    assert_ne!(first.ciphertext, second.ciphertext);
+}
@@ -0,0 +1,2 @@
+// Client generated by the wasm build script, shared by the host-side crates
+include!("../../wasm/app_client.rs");
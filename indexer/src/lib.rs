@@ -0,0 +1,200 @@
+// Off-chain indexer that rebuilds the groups of the program from the events it
+// emits, without querying the program state
+pub mod view;
+
+use client::service::events::ServiceEvents;
+use client::{DisputeStatus, ExpenseStatus, PaymentStatus};
+use sails_rs::events::EventIo;
+use std::collections::BTreeMap;
+
+pub use view::*;
+
+#[derive(Debug)]
+pub enum IndexerError {
+    // The payload is not an event of the service
+    Decode(String),
+    // The event refers to a group that was never created
+    UnknownGroup(u32),
+}
+
+#[derive(Default)]
+pub struct Indexer {
+    pub groups: BTreeMap<u32, GroupView>,
+    pub paused: bool,
+    // Every event applied, in the order they were received
+    pub history: Vec<ServiceEvents>,
+    // Expense of each dispute, the resolution event only carries the dispute id
+    disputes: BTreeMap<u32, u32>,
+}
+
+impl Indexer {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    // Decodes the raw payload of an event (route, event name and data) and
+    // applies it to the view
+    pub fn ingest(&mut self, payload: &[u8]) -> Result<(), IndexerError> {
+        let event = ServiceEvents::decode_event(payload)
+            .map_err(|error| IndexerError::Decode(format!("{error:?}")))?;
+
+        self.apply(event)
+    }
+
+    // Applies a list of raw payloads, stopping at the first one that fails
+    pub fn ingest_all<'a>(&mut self, payloads: impl IntoIterator<Item = &'a [u8]>) -> Result<(), IndexerError> {
+        payloads.into_iter().try_for_each(|payload| self.ingest(payload))
+    }
+
+    pub fn apply(&mut self, event: ServiceEvents) -> Result<(), IndexerError> {
+        match &event {
+            ServiceEvents::ProgramPaused => self.paused = true,
+            ServiceEvents::ProgramUnpaused => self.paused = false,
+            ServiceEvents::GroupCreated { group_id, name, creator } => {
+                self.groups.insert(*group_id, GroupView::new(*group_id, name.clone(), *creator));
+            }
+            ServiceEvents::MemberJoined { group_id, actor_id } => {
+                self.group_mut(*group_id)?.members.push(*actor_id);
+            }
+            ServiceEvents::GroupUpdated { group_id, metadata } => {
+                let group = self.group_mut(*group_id)?;
+                group.name = metadata.name.clone();
+                group.description = metadata.description.clone();
+                group.default_currency = metadata.default_currency.clone();
+            }
+            ServiceEvents::AdminAdded { group_id, actor_id } => {
+                self.group_mut(*group_id)?.admins.push(*actor_id);
+            }
+            ServiceEvents::GroupClosed { group_id, .. } => {
+                let group = self.group_mut(*group_id)?;
+                group.closed = true;
+                group.pool.clear();
+            }
+            ServiceEvents::ExpenseAdded { group_id, expense } => {
+                self.group_mut(*group_id)?.expenses.push(expense.clone());
+            }
+            ServiceEvents::ExpenseApprovalAdded { group_id, expense_id, actor_id } => {
+                if let Some(expense) = self.group_mut(*group_id)?.expense_mut(*expense_id) {
+                    expense.approvals.push(*actor_id);
+                }
+            }
+            ServiceEvents::ExpenseApproved { group_id, expense_id } => {
+                if let Some(expense) = self.group_mut(*group_id)?.expense_mut(*expense_id) {
+                    expense.status = ExpenseStatus::Approved;
+                }
+            }
+            ServiceEvents::ExpenseRejected { group_id, expense_id, .. } => {
+                if let Some(expense) = self.group_mut(*group_id)?.expense_mut(*expense_id) {
+                    expense.status = ExpenseStatus::Rejected;
+                }
+            }
+            ServiceEvents::DisputeOpened { group_id, dispute } => {
+                self.group_mut(*group_id)?.disputed.insert(dispute.expense_id);
+                self.disputes.insert(dispute.id, dispute.expense_id);
+            }
+            ServiceEvents::DisputeResolved { group_id, dispute_id, status, expense_status } => {
+                let expense_id = self.disputes.get(dispute_id).copied();
+                let group = self.group_mut(*group_id)?;
+
+                if let Some(expense_id) = expense_id {
+                    group.disputed.remove(&expense_id);
+
+                    if let Some(expense) = group.expense_mut(expense_id) {
+                        if let DisputeStatus::Amended(amount) = status {
+                            expense.amount = *amount;
+                        }
+                        // An amended expense that goes back to pending must be
+                        // approved again
+                        if *expense_status == ExpenseStatus::Pending && expense.status != ExpenseStatus::Pending {
+                            expense.approvals.clear();
+                        }
+                        expense.status = expense_status.clone();
+                    }
+                }
+            }
+            ServiceEvents::PaymentAdded { group_id, payment } => {
+                self.group_mut(*group_id)?.payments.push(payment.clone());
+            }
+            ServiceEvents::PaymentConfirmed { group_id, payment_id } => {
+                if let Some(payment) = self.group_mut(*group_id)?.payment_mut(*payment_id) {
+                    payment.status = PaymentStatus::Confirmed;
+                }
+            }
+            ServiceEvents::PaymentRejected { group_id, payment_id } => {
+                if let Some(payment) = self.group_mut(*group_id)?.payment_mut(*payment_id) {
+                    payment.status = PaymentStatus::Rejected;
+                }
+            }
+            ServiceEvents::Deposited { group_id, actor_id, amount, .. } => {
+                self.group_mut(*group_id)?.deposit_to_pool(*actor_id, *amount);
+            }
+            ServiceEvents::PoolPaid { group_id, payment } => {
+                self.group_mut(*group_id)?.debit_pool(payment.amount);
+            }
+            ServiceEvents::WithdrawalExecuted { group_id, amount, .. } => {
+                self.group_mut(*group_id)?.debit_pool(*amount);
+            }
+            // Recurring expenses, budgets, approval policies and quorums do not
+            // change the ledger, they are only kept in the history
+            _ => {}
+        }
+
+        self.history.push(event);
+
+        Ok(())
+    }
+
+    pub fn group(&self, group_id: u32) -> Option<&GroupView> {
+        self.groups.get(&group_id)
+    }
+
+    // Events that concern the group, in the order they were received
+    pub fn group_history(&self, group_id: u32) -> impl Iterator<Item = &ServiceEvents> {
+        self.history
+            .iter()
+            .filter(move |event| event_group_id(event) == Some(group_id))
+    }
+
+    fn group_mut(&mut self, group_id: u32) -> Result<&mut GroupView, IndexerError> {
+        self.groups
+            .get_mut(&group_id)
+            .ok_or(IndexerError::UnknownGroup(group_id))
+    }
+}
+
+// Group the event refers to, program events do not belong to any group
+pub fn event_group_id(event: &ServiceEvents) -> Option<u32> {
+    match event {
+        ServiceEvents::ProgramPaused | ServiceEvents::ProgramUnpaused => None,
+        ServiceEvents::WithdrawalProposed { proposal } => Some(proposal.group_id),
+        ServiceEvents::GroupCreated { group_id, .. }
+        | ServiceEvents::MemberJoined { group_id, .. }
+        | ServiceEvents::GroupUpdated { group_id, .. }
+        | ServiceEvents::AdminAdded { group_id, .. }
+        | ServiceEvents::GroupClosed { group_id, .. }
+        | ServiceEvents::ExpenseAdded { group_id, .. }
+        | ServiceEvents::RecurringExpenseCreated { group_id, .. }
+        | ServiceEvents::RecurringExpensePaused { group_id, .. }
+        | ServiceEvents::RecurringExpenseResumed { group_id, .. }
+        | ServiceEvents::RecurringExpenseCancelled { group_id, .. }
+        | ServiceEvents::RecurringExpenseStopped { group_id, .. }
+        | ServiceEvents::ApprovalPolicySet { group_id, .. }
+        | ServiceEvents::ExpenseApprovalAdded { group_id, .. }
+        | ServiceEvents::ExpenseApproved { group_id, .. }
+        | ServiceEvents::ExpenseRejected { group_id, .. }
+        | ServiceEvents::DisputeOpened { group_id, .. }
+        | ServiceEvents::DisputeResolved { group_id, .. }
+        | ServiceEvents::BudgetSet { group_id, .. }
+        | ServiceEvents::BudgetRemoved { group_id, .. }
+        | ServiceEvents::BudgetThresholdReached { group_id, .. }
+        | ServiceEvents::BudgetExceeded { group_id, .. }
+        | ServiceEvents::PaymentAdded { group_id, .. }
+        | ServiceEvents::PaymentConfirmed { group_id, .. }
+        | ServiceEvents::PaymentRejected { group_id, .. }
+        | ServiceEvents::Deposited { group_id, .. }
+        | ServiceEvents::PoolPaid { group_id, .. }
+        | ServiceEvents::WithdrawalQuorumSet { group_id, .. }
+        | ServiceEvents::WithdrawalApproved { group_id, .. }
+        | ServiceEvents::WithdrawalExecuted { group_id, .. } => Some(*group_id),
+    }
+}
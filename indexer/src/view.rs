@@ -0,0 +1,77 @@
+use client::{Balance, Expense, ExpenseStatus, Payment, PaymentStatus, PoolBalance};
+use sails_rs::ActorId;
+use std::collections::BTreeSet;
+
+// Materialized view of a group, built only from the events of the program
+#[derive(Debug, Clone, Default)]
+pub struct GroupView {
+    pub id: u32,
+    pub name: String,
+    pub description: String,
+    pub default_currency: String,
+    pub admins: Vec<ActorId>,
+    pub members: Vec<ActorId>,
+    pub expenses: Vec<Expense>,
+    pub payments: Vec<Payment>,
+    pub pool: Vec<PoolBalance>,
+    // Expenses with an open dispute
+    pub disputed: BTreeSet<u32>,
+    pub closed: bool,
+}
+
+impl GroupView {
+    pub fn new(id: u32, name: String, creator: ActorId) -> Self {
+        Self {
+            id,
+            name,
+            admins: vec![creator],
+            members: vec![creator],
+            ..Default::default()
+        }
+    }
+
+    // Balances of the members, computed with the same entries as the program:
+    // approved expenses without an open dispute and confirmed payments
+    pub fn balances(&self) -> Vec<Balance> {
+        let expenses = self.expenses
+            .iter()
+            .filter(|e| e.status == ExpenseStatus::Approved && !self.disputed.contains(&e.id))
+            .map(|e| math::Movement::Expense { payer: e.actor_id, amount: e.amount, participants: &e.participants });
+        let payments = self.payments
+            .iter()
+            .filter(|p| p.status == PaymentStatus::Confirmed)
+            .map(|p| math::Movement::Payment { from: p.from, to: p.to, amount: p.amount as u128 });
+
+        math::balances(&self.members, expenses.chain(payments))
+            .into_iter()
+            .map(|(actor_id, amount)| Balance { actor_id, amount })
+            .collect()
+    }
+
+    pub fn pool_total(&self) -> u128 {
+        self.pool.iter().map(|p| p.amount).sum()
+    }
+
+    pub(crate) fn expense_mut(&mut self, expense_id: u32) -> Option<&mut Expense> {
+        self.expenses.iter_mut().find(|e| e.id == expense_id)
+    }
+
+    pub(crate) fn payment_mut(&mut self, payment_id: u32) -> Option<&mut Payment> {
+        self.payments.iter_mut().find(|p| p.id == payment_id)
+    }
+
+    pub(crate) fn deposit_to_pool(&mut self, actor_id: ActorId, amount: u128) {
+        match self.pool.iter_mut().find(|p| p.actor_id == actor_id) {
+            Some(balance) => balance.amount += amount,
+            None => self.pool.push(PoolBalance { actor_id, amount }),
+        }
+    }
+
+    // Same pro-rata debit as the program, so the view matches its treasury
+    pub(crate) fn debit_pool(&mut self, amount: u128) {
+        let mut funds: Vec<&mut u128> = self.pool.iter_mut().map(|p| &mut p.amount).collect();
+        math::debit_pro_rata(&mut funds, amount);
+
+        self.pool.retain(|p| p.amount > 0);
+    }
+}
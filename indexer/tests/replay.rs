@@ -0,0 +1,191 @@
+use client::service::events::ServiceEvents;
+use client::{Expense, ExpenseCategory, ExpenseStatus, Payment, PaymentStatus};
+use indexer::{Indexer, IndexerError};
+use sails_rs::{events::EventIo, ActorId, Encode};
+
+const ALICE: u64 = 10;
+const BOB: u64 = 11;
+const CAROL: u64 = 12;
+
+// Payload of the event as emitted by the program: service route, event name
+// and the fields of the variant
+fn payload(event: ServiceEvents) -> Vec<u8> {
+    let encoded = event.encode();
+    let name = ServiceEvents::EVENT_NAMES[encoded[0] as usize];
+
+    [ServiceEvents::ROUTE, name, &encoded[1..]].concat()
+}
+
+fn expense(id: u32, payer: u64, amount: u128, participants: &[u64]) -> Expense {
+    Expense {
+        id,
+        description: "Dinner".to_owned(),
+        amount,
+        currency: "VARA".to_owned(),
+        category: ExpenseCategory::Food,
+        tags: vec![],
+        actor_id: payer.into(),
+        participants: participants.iter().map(|p| ActorId::from(*p)).collect(),
+        status: ExpenseStatus::Approved,
+        approvals: vec![],
+        created_block: 0,
+        created_at: 0,
+    }
+}
+
+fn balance(indexer: &Indexer, group_id: u32, actor: u64) -> i128 {
+    indexer
+        .group(group_id)
+        .unwrap()
+        .balances()
+        .iter()
+        .find(|b| b.actor_id == actor.into())
+        .map(|b| b.amount)
+        .unwrap_or_default()
+}
+
+fn group_with_members(indexer: &mut Indexer) {
+    indexer.ingest(&payload(ServiceEvents::GroupCreated {
+        group_id: 1,
+        name: "Trip".to_owned(),
+        creator: ALICE.into(),
+    })).unwrap();
+
+    for member in [BOB, CAROL] {
+        indexer.ingest(&payload(ServiceEvents::MemberJoined {
+            group_id: 1,
+            actor_id: member.into(),
+        })).unwrap();
+    }
+}
+
+#[test]
+fn rebuilds_balances_from_events() {
+    let mut indexer = Indexer::new();
+    group_with_members(&mut indexer);
+
+    indexer.ingest(&payload(ServiceEvents::ExpenseAdded {
+        group_id: 1,
+        expense: expense(2, ALICE, 300, &[ALICE, BOB, CAROL]),
+    })).unwrap();
+    indexer.ingest(&payload(ServiceEvents::PaymentAdded {
+        group_id: 1,
+        payment: Payment {
+            id: 3,
+            from: BOB.into(),
+            to: ALICE.into(),
+            amount: 100,
+            status: PaymentStatus::Unconfirmed,
+            created_block: 0,
+            created_at: 0,
+        },
+    })).unwrap();
+
+    assert_eq!(balance(&indexer, 1, ALICE), 200);
+    assert_eq!(balance(&indexer, 1, BOB), -100);
+
+    indexer.ingest(&payload(ServiceEvents::PaymentConfirmed { group_id: 1, payment_id: 3 })).unwrap();
+
+    assert_eq!(balance(&indexer, 1, ALICE), 100);
+    assert_eq!(balance(&indexer, 1, BOB), 0);
+    assert_eq!(balance(&indexer, 1, CAROL), -100);
+}
+
+#[test]
+fn pending_and_disputed_expenses_do_not_count() {
+    let mut indexer = Indexer::new();
+    group_with_members(&mut indexer);
+
+    let mut pending = expense(2, ALICE, 90, &[ALICE, BOB, CAROL]);
+    pending.status = ExpenseStatus::Pending;
+
+    indexer.ingest(&payload(ServiceEvents::ExpenseAdded { group_id: 1, expense: pending })).unwrap();
+    assert_eq!(balance(&indexer, 1, ALICE), 0);
+
+    indexer.ingest(&payload(ServiceEvents::ExpenseApproved { group_id: 1, expense_id: 2 })).unwrap();
+    assert_eq!(balance(&indexer, 1, ALICE), 60);
+
+    indexer.ingest(&payload(ServiceEvents::DisputeOpened {
+        group_id: 1,
+        dispute: client::Dispute {
+            id: 4,
+            expense_id: 2,
+            opened_by: BOB.into(),
+            reason: "Wrong amount".to_owned(),
+            status: client::DisputeStatus::Open,
+        },
+    })).unwrap();
+    assert_eq!(balance(&indexer, 1, ALICE), 0);
+
+    indexer.ingest(&payload(ServiceEvents::DisputeResolved {
+        group_id: 1,
+        dispute_id: 4,
+        status: client::DisputeStatus::Amended(30),
+        expense_status: ExpenseStatus::Approved,
+    })).unwrap();
+    assert_eq!(balance(&indexer, 1, ALICE), 20);
+    assert_eq!(balance(&indexer, 1, BOB), -10);
+}
+
+#[test]
+fn amended_expenses_can_go_back_to_pending() {
+    let mut indexer = Indexer::new();
+    group_with_members(&mut indexer);
+
+    let mut approved = expense(2, ALICE, 90, &[ALICE, BOB, CAROL]);
+    approved.approvals = vec![BOB.into()];
+
+    indexer.ingest(&payload(ServiceEvents::ExpenseAdded { group_id: 1, expense: approved })).unwrap();
+    indexer.ingest(&payload(ServiceEvents::DisputeOpened {
+        group_id: 1,
+        dispute: client::Dispute {
+            id: 4,
+            expense_id: 2,
+            opened_by: BOB.into(),
+            reason: "Wrong amount".to_owned(),
+            status: client::DisputeStatus::Open,
+        },
+    })).unwrap();
+    indexer.ingest(&payload(ServiceEvents::DisputeResolved {
+        group_id: 1,
+        dispute_id: 4,
+        status: client::DisputeStatus::Amended(900),
+        expense_status: ExpenseStatus::Pending,
+    })).unwrap();
+
+    let expense = &indexer.group(1).unwrap().expenses[0];
+    assert_eq!(expense.amount, 900);
+    assert_eq!(expense.status, ExpenseStatus::Pending);
+    assert!(expense.approvals.is_empty());
+    assert_eq!(balance(&indexer, 1, ALICE), 0);
+}
+
+#[test]
+fn keeps_the_history_of_each_group() {
+    let mut indexer = Indexer::new();
+    group_with_members(&mut indexer);
+
+    indexer.ingest(&payload(ServiceEvents::GroupCreated {
+        group_id: 5,
+        name: "Flat".to_owned(),
+        creator: BOB.into(),
+    })).unwrap();
+    indexer.ingest(&payload(ServiceEvents::ProgramPaused)).unwrap();
+
+    assert!(indexer.paused);
+    assert_eq!(indexer.history.len(), 5);
+    assert_eq!(indexer.group_history(1).count(), 3);
+    assert_eq!(indexer.group_history(5).count(), 1);
+}
+
+#[test]
+fn rejects_unknown_payloads_and_groups() {
+    let mut indexer = Indexer::new();
+
+    assert!(matches!(indexer.ingest(&[1, 2, 3]), Err(IndexerError::Decode(_))));
+    assert!(matches!(
+        indexer.ingest(&payload(ServiceEvents::MemberJoined { group_id: 9, actor_id: BOB.into() })),
+        Err(IndexerError::UnknownGroup(9))
+    ));
+    assert!(indexer.history.is_empty());
+}
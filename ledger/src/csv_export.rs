@@ -0,0 +1,165 @@
+use crate::{format_actor_id, format_date};
+use client::{DisputeStatus, Expense, ExpenseCategory, ExpenseStatus, Group, Payment, PaymentStatus};
+use sails_rs::ActorId;
+
+// One line of the ledger, expenses and payments share the id counter of the
+// program so sorting by id gives the order they were added
+enum Entry<'a> {
+    Expense(&'a Expense),
+    Payment(&'a Payment),
+}
+
+impl Entry<'_> {
+    fn id(&self) -> u32 {
+        match self {
+            Entry::Expense(expense) => expense.id,
+            Entry::Payment(payment) => payment.id,
+        }
+    }
+
+    fn created_at(&self) -> u64 {
+        match self {
+            Entry::Expense(expense) => expense.created_at,
+            Entry::Payment(payment) => payment.created_at,
+        }
+    }
+
+    // Movement of the entry in the balances, with the same entries as the
+    // program: approved expenses without an open dispute and confirmed payments
+    fn movement(&self, group: &Group) -> Option<math::Movement<'_, ActorId>> {
+        match self {
+            Entry::Expense(expense) if expense.status == ExpenseStatus::Approved && !is_disputed(group, expense.id) => {
+                Some(math::Movement::Expense {
+                    payer: expense.actor_id,
+                    amount: expense.amount,
+                    participants: &expense.participants,
+                })
+            }
+            Entry::Payment(payment) if payment.status == PaymentStatus::Confirmed => {
+                Some(math::Movement::Payment { from: payment.from, to: payment.to, amount: payment.amount as u128 })
+            }
+            _ => None,
+        }
+    }
+}
+
+// Renders the ledger of a group as CSV, one row per expense and payment with
+// the running balance of every member after it. Accounts that are not members
+// anymore get a column after the members. The date is the UTC date of the
+// block where the entry was added
+pub fn group_ledger_csv(group: &Group) -> Result<String, csv::Error> {
+    let mut writer = csv::Writer::from_writer(Vec::new());
+
+    let mut entries: Vec<Entry> = group.expenses
+        .iter()
+        .map(Entry::Expense)
+        .chain(group.payments.iter().map(Entry::Payment))
+        .collect();
+    entries.sort_by_key(Entry::id);
+
+    // One column per account of the final balances, the running balances
+    // start at zero for all of them so the columns stay the same on every row
+    let mut balances = math::balances(&group.members, entries.iter().filter_map(|e| e.movement(group)));
+    balances.iter_mut().for_each(|(_, amount)| *amount = 0);
+
+    let mut header = vec![
+        "date".to_owned(),
+        "type".to_owned(),
+        "id".to_owned(),
+        "status".to_owned(),
+        "payer".to_owned(),
+        "participants".to_owned(),
+        "amount".to_owned(),
+        "currency".to_owned(),
+        "category".to_owned(),
+        "description".to_owned(),
+    ];
+    header.extend(balances.iter().map(|(account, _)| format_actor_id(account)));
+    writer.write_record(&header)?;
+
+    for entry in &entries {
+        for (account, amount) in math::balances(&[], entry.movement(group)) {
+            math::add_to_balance(&mut balances, account, amount);
+        }
+
+        let date = format_date(entry.created_at());
+
+        let mut record = match entry {
+            Entry::Expense(expense) => {
+                vec![
+                    date,
+                    "expense".to_owned(),
+                    expense.id.to_string(),
+                    expense_status(group, expense).to_owned(),
+                    format_actor_id(&expense.actor_id),
+                    expense.participants.iter().map(format_actor_id).collect::<Vec<_>>().join(";"),
+                    expense.amount.to_string(),
+                    expense.currency.clone(),
+                    category_name(&expense.category),
+                    expense.description.clone(),
+                ]
+            }
+            Entry::Payment(payment) => {
+                vec![
+                    date,
+                    "payment".to_owned(),
+                    payment.id.to_string(),
+                    payment_status(&payment.status).to_owned(),
+                    format_actor_id(&payment.from),
+                    format_actor_id(&payment.to),
+                    payment.amount.to_string(),
+                    String::new(),
+                    String::new(),
+                    String::new(),
+                ]
+            }
+        };
+
+        record.extend(balances.iter().map(|(_, amount)| amount.to_string()));
+        writer.write_record(&record)?;
+    }
+
+    let bytes = writer
+        .into_inner()
+        .map_err(|error| csv::Error::from(error.into_error()))?;
+
+    Ok(String::from_utf8(bytes).expect("CSV output is UTF-8"))
+}
+
+fn is_disputed(group: &Group, expense_id: u32) -> bool {
+    group.disputes
+        .iter()
+        .any(|d| d.expense_id == expense_id && d.status == DisputeStatus::Open)
+}
+
+fn expense_status(group: &Group, expense: &Expense) -> &'static str {
+    if is_disputed(group, expense.id) {
+        return "disputed";
+    }
+    match expense.status {
+        ExpenseStatus::Approved => "approved",
+        ExpenseStatus::Pending => "pending",
+        ExpenseStatus::Rejected => "rejected",
+        ExpenseStatus::Voided => "voided",
+    }
+}
+
+fn payment_status(status: &PaymentStatus) -> &'static str {
+    match status {
+        PaymentStatus::Unconfirmed => "unconfirmed",
+        PaymentStatus::Confirmed => "confirmed",
+        PaymentStatus::Rejected => "rejected",
+    }
+}
+
+pub fn category_name(category: &ExpenseCategory) -> String {
+    match category {
+        ExpenseCategory::Food => "food".to_owned(),
+        ExpenseCategory::Transport => "transport".to_owned(),
+        ExpenseCategory::Lodging => "lodging".to_owned(),
+        ExpenseCategory::Utilities => "utilities".to_owned(),
+        ExpenseCategory::Entertainment => "entertainment".to_owned(),
+        ExpenseCategory::Other(name) if name.is_empty() => "other".to_owned(),
+        ExpenseCategory::Other(name) => name.clone(),
+    }
+}
@@ -0,0 +1,282 @@
+use client::{ExpenseCategory, ExpenseDto};
+use sails_rs::ActorId;
+use std::{cmp::Reverse, collections::BTreeMap, fmt, io::Read};
+
+// Columns of a Splitwise export before the one column per member, the value
+// of a member column is what they paid minus their share
+const SPLITWISE_COLUMNS: [&str; 5] = ["Date", "Description", "Category", "Cost", "Currency"];
+
+#[derive(Debug, Clone)]
+pub struct ImportOptions {
+    // Decimals of the amounts in the CSV that are kept in the minor units of
+    // the program (e.g. 2 turns 12.50 into 1250)
+    pub decimals: u32,
+    // Maximum expenses of an `AddExpensesBatch` message
+    pub batch_size: usize,
+}
+
+impl Default for ImportOptions {
+    fn default() -> Self {
+        Self {
+            decimals: 2,
+            batch_size: 50,
+        }
+    }
+}
+
+#[derive(Debug)]
+pub enum ImportError {
+    Csv(csv::Error),
+    MissingColumn(&'static str),
+    // A member column of the export is not in the mapping file
+    UnknownMember(String),
+    InvalidAccount { name: String, account: String },
+}
+
+impl fmt::Display for ImportError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ImportError::Csv(error) => write!(f, "Invalid CSV: {error}"),
+            ImportError::MissingColumn(column) => write!(f, "Missing column {column}"),
+            ImportError::UnknownMember(name) => write!(f, "Member {name} is not in the mapping"),
+            ImportError::InvalidAccount { name, account } => write!(f, "Invalid account {account} for {name}"),
+        }
+    }
+}
+
+impl std::error::Error for ImportError {}
+
+impl From<csv::Error> for ImportError {
+    fn from(error: csv::Error) -> Self {
+        ImportError::Csv(error)
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct ImportedExpense {
+    pub line: u64,
+    pub payer: ActorId,
+    pub expense: ExpenseDto,
+}
+
+// Row of the export that cannot be added as an expense
+#[derive(Debug, Clone, PartialEq)]
+pub struct SkippedRow {
+    pub line: u64,
+    pub reason: String,
+}
+
+#[derive(Debug, Default)]
+pub struct Import {
+    pub expenses: Vec<ImportedExpense>,
+    pub skipped: Vec<SkippedRow>,
+}
+
+impl Import {
+    // Expenses grouped in `AddExpensesBatch` messages, the program takes the
+    // sender as payer so each batch must be sent by its payer
+    pub fn batches(&self, batch_size: usize) -> Vec<(ActorId, Vec<ExpenseDto>)> {
+        let mut by_payer: BTreeMap<ActorId, Vec<ExpenseDto>> = BTreeMap::new();
+
+        for imported in self.expenses.iter() {
+            by_payer.entry(imported.payer).or_default().push(imported.expense.clone());
+        }
+
+        by_payer
+            .into_iter()
+            .flat_map(|(payer, expenses)| {
+                expenses
+                    .chunks(batch_size.max(1))
+                    .map(|chunk| (payer, chunk.to_vec()))
+                    .collect::<Vec<_>>()
+            })
+            .collect()
+    }
+}
+
+// Reads the mapping of the member names of the export to their accounts, a CSV
+// with the `name` and `account` columns
+pub fn parse_mapping(reader: impl Read) -> Result<BTreeMap<String, ActorId>, ImportError> {
+    let mut reader = csv::Reader::from_reader(reader);
+    let mut mapping = BTreeMap::new();
+
+    for record in reader.records() {
+        let record = record?;
+        let name = record.get(0).ok_or(ImportError::MissingColumn("name"))?.trim();
+        let account = record.get(1).ok_or(ImportError::MissingColumn("account"))?.trim();
+
+        let actor_id = parse_account(account).ok_or_else(|| ImportError::InvalidAccount {
+            name: name.to_owned(),
+            account: account.to_owned(),
+        })?;
+
+        mapping.insert(name.to_owned(), actor_id);
+    }
+
+    Ok(mapping)
+}
+
+// Converts a Splitwise export into expenses of the program. The program splits
+// the expenses equally with a single payer, rows that cannot be represented
+// that way (payments, several payers, unequal splits) are reported as skipped
+pub fn import_splitwise_csv(
+    reader: impl Read,
+    mapping: &BTreeMap<String, ActorId>,
+    options: &ImportOptions,
+) -> Result<Import, ImportError> {
+    let mut reader = csv::ReaderBuilder::new().flexible(true).from_reader(reader);
+
+    let headers = reader.headers()?.clone();
+    for (index, column) in SPLITWISE_COLUMNS.iter().enumerate() {
+        if headers.get(index).map(str::trim) != Some(*column) {
+            return Err(ImportError::MissingColumn(column));
+        }
+    }
+
+    let members = headers
+        .iter()
+        .skip(SPLITWISE_COLUMNS.len())
+        .map(|name| {
+            mapping
+                .get(name.trim())
+                .copied()
+                .ok_or_else(|| ImportError::UnknownMember(name.trim().to_owned()))
+        })
+        .collect::<Result<Vec<ActorId>, ImportError>>()?;
+
+    let mut import = Import::default();
+
+    for record in reader.records() {
+        let record = record?;
+        let line = record.position().map(|p| p.line()).unwrap_or_default();
+
+        let date = record.get(0).unwrap_or_default().trim();
+        let description = record.get(1).unwrap_or_default().trim();
+
+        // Rows without a date and the final "Total balance" row
+        if date.is_empty() || description == "Total balance" {
+            continue;
+        }
+
+        match parse_row(&record, &members, options) {
+            Ok((payer, mut expense)) => {
+                expense.description = description.to_owned();
+                expense.tags = vec![date.to_owned()];
+                import.expenses.push(ImportedExpense { line, payer, expense });
+            }
+            Err(reason) => import.skipped.push(SkippedRow { line, reason }),
+        }
+    }
+
+    Ok(import)
+}
+
+fn parse_row(record: &csv::StringRecord, members: &[ActorId], options: &ImportOptions) -> Result<(ActorId, ExpenseDto), String> {
+    let category = record.get(2).unwrap_or_default().trim();
+    if category.eq_ignore_ascii_case("payment") {
+        return Err("Payments between members are not imported".to_owned());
+    }
+
+    let cost = parse_amount(record.get(3).unwrap_or_default(), options.decimals)?;
+    if cost <= 0 {
+        return Err("Cost must be greater than zero".to_owned());
+    }
+
+    let nets = members
+        .iter()
+        .enumerate()
+        .map(|(index, member)| {
+            let value = record.get(SPLITWISE_COLUMNS.len() + index).unwrap_or_default();
+            Ok((*member, if value.trim().is_empty() { 0 } else { parse_amount(value, options.decimals)? }))
+        })
+        .collect::<Result<Vec<(ActorId, i128)>, String>>()?;
+
+    let payers: Vec<&(ActorId, i128)> = nets.iter().filter(|(_, net)| *net > 0).collect();
+    let [(payer, payer_net)] = payers.as_slice() else {
+        return Err("Expense must have a single payer".to_owned());
+    };
+
+    // Share of every participant, the payer takes part when they paid more
+    // than what they are owed
+    let mut shares: Vec<(ActorId, i128)> = nets
+        .iter()
+        .map(|(member, net)| {
+            let share = if member == payer { cost - payer_net } else { -net };
+            (*member, share)
+        })
+        .filter(|(_, share)| *share > 0)
+        .collect();
+
+    if shares.is_empty() || shares.iter().map(|(_, share)| share).sum::<i128>() != cost {
+        return Err("Shares do not add up to the cost".to_owned());
+    }
+
+    // The program splits equally and gives the remainder to the first
+    // participants, so the larger shares go first
+    shares.sort_by_key(|share| Reverse(share.1));
+    let (largest, smallest) = (shares[0].1, shares[shares.len() - 1].1);
+    if largest - smallest > 1 {
+        return Err("Unequal splits are not supported".to_owned());
+    }
+
+    let expense = ExpenseDto {
+        description: String::new(),
+        amount: cost as u128,
+        currency: record.get(4).unwrap_or_default().trim().to_owned(),
+        category: parse_category(category),
+        tags: vec![],
+        participants: shares.into_iter().map(|(member, _)| member).collect(),
+    };
+
+    Ok((*payer, expense))
+}
+
+// Parses a decimal amount into minor units
+fn parse_amount(text: &str, decimals: u32) -> Result<i128, String> {
+    let text = text.trim();
+    let (negative, digits) = match text.strip_prefix('-') {
+        Some(digits) => (true, digits),
+        None => (false, text),
+    };
+    let (whole, fraction) = digits.split_once('.').unwrap_or((digits, ""));
+
+    if fraction.len() > decimals as usize {
+        return Err(format!("Amount {text} has more than {decimals} decimals"));
+    }
+
+    let fraction = format!("{fraction:0<width$}", width = decimals as usize);
+    let amount: i128 = format!("{whole}{fraction}")
+        .parse()
+        .map_err(|_| format!("Invalid amount {text}"))?;
+
+    Ok(if negative { -amount } else { amount })
+}
+
+fn parse_account(text: &str) -> Option<ActorId> {
+    let bytes: [u8; 32] = hex::decode(text.trim_start_matches("0x")).ok()?.try_into().ok()?;
+    Some(ActorId::from(bytes))
+}
+
+// Maps the Splitwise categories to the categories of the program. The words of
+// the name are compared, so "Heat/gas" is a utility and "Gas/fuel" transport
+fn parse_category(name: &str) -> ExpenseCategory {
+    let lower = name.to_lowercase();
+    let name_words: Vec<&str> = lower.split(|c: char| !c.is_alphanumeric()).collect();
+    let matches = |words: &[&str]| words.iter().any(|word| name_words.contains(word));
+
+    if matches(&["food", "dining", "groceries", "restaurant", "liquor"]) {
+        ExpenseCategory::Food
+    } else if matches(&["transportation", "transport", "taxi", "bus", "train", "car", "fuel", "parking", "plane", "bicycle"]) {
+        ExpenseCategory::Transport
+    } else if matches(&["hotel", "lodging", "rent", "mortgage"]) {
+        ExpenseCategory::Lodging
+    } else if matches(&["utilities", "electricity", "water", "heat", "internet", "phone", "tv", "trash"]) {
+        ExpenseCategory::Utilities
+    } else if matches(&["entertainment", "movies", "games", "music", "sports"]) {
+        ExpenseCategory::Entertainment
+    } else if lower.is_empty() || lower == "general" {
+        ExpenseCategory::Other(String::new())
+    } else {
+        ExpenseCategory::Other(name.to_owned())
+    }
+}
@@ -0,0 +1,493 @@
+// JSON view of the program state for consumers without a SCALE decoder.
+//
+// The schema is versioned by `SCHEMA_VERSION` and only changes with it:
+// - accounts (`ActorId`) and hashes are `0x` prefixed hex strings
+// - amounts and balances (`u128`, `i128`) are decimal strings, since they do
+//   not fit in a JSON number; ids, counts, block numbers and timestamps (in
+//   milliseconds) are numbers
+// - enums without data are snake case strings (e.g. `"approved"`)
+// - enums with data are objects with a `kind` and a `value` (e.g.
+//   `{"kind": "other", "value": "Gym"}`), `value` is absent when there is no data
+// - optional values are `null` when not set
+use crate::format_actor_id;
+use client::{
+    ApprovalPolicy, ArchivedGroup, Balance, Budget, CategoryTotal, Dispute, DisputeStatus, Expense,
+    ExpenseCategory, ExpenseDto, ExpenseStatus, Group, IoState, Payment, PaymentStatus, PoolBalance,
+    PoolPayment, RecurringExpense, SplitMode, Transfer, WithdrawalProposal,
+};
+use serde::Serialize;
+
+pub const SCHEMA_VERSION: u32 = 1;
+
+#[derive(Serialize)]
+pub struct StateJson {
+    pub schema_version: u32,
+    pub groups: Vec<GroupJson>,
+    pub withdrawals: Vec<WithdrawalJson>,
+    pub archived_groups: Vec<ArchivedGroupJson>,
+}
+
+#[derive(Serialize)]
+pub struct GroupJson {
+    pub id: u32,
+    pub name: String,
+    pub description: String,
+    pub avatar_uri: String,
+    pub avatar_hash: Option<String>,
+    pub default_split_mode: SplitModeJson,
+    pub default_currency: String,
+    pub admins: Vec<String>,
+    pub members: Vec<String>,
+    pub expenses: Vec<ExpenseJson>,
+    pub payments: Vec<PaymentJson>,
+    pub recurring_expenses: Vec<RecurringExpenseJson>,
+    pub budgets: Vec<BudgetJson>,
+    pub approval_policy: ApprovalPolicyJson,
+    pub disputes: Vec<DisputeJson>,
+    pub pool: Vec<AmountJson>,
+    pub pool_payments: Vec<PoolPaymentJson>,
+    pub withdrawal_quorum: u32,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SplitModeJson {
+    Equal,
+    Percentage,
+    Exact,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "snake_case", tag = "kind", content = "value")]
+pub enum CategoryJson {
+    Food,
+    Transport,
+    Lodging,
+    Utilities,
+    Entertainment,
+    Other(String),
+}
+
+#[derive(Serialize)]
+pub struct ExpenseJson {
+    pub id: u32,
+    pub description: String,
+    pub amount: String,
+    pub currency: String,
+    pub category: CategoryJson,
+    pub tags: Vec<String>,
+    pub payer: String,
+    pub participants: Vec<String>,
+    pub status: ExpenseStatusJson,
+    pub approvals: Vec<String>,
+    pub created_block: u32,
+    pub created_at: u64,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ExpenseStatusJson {
+    Approved,
+    Pending,
+    Rejected,
+    Voided,
+}
+
+#[derive(Serialize)]
+pub struct ExpenseTemplateJson {
+    pub description: String,
+    pub amount: String,
+    pub currency: String,
+    pub category: CategoryJson,
+    pub tags: Vec<String>,
+    pub participants: Vec<String>,
+}
+
+#[derive(Serialize)]
+pub struct PaymentJson {
+    pub id: u32,
+    pub from: String,
+    pub to: String,
+    pub amount: String,
+    pub status: PaymentStatusJson,
+    pub created_block: u32,
+    pub created_at: u64,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PaymentStatusJson {
+    Unconfirmed,
+    Confirmed,
+    Rejected,
+}
+
+#[derive(Serialize)]
+pub struct RecurringExpenseJson {
+    pub id: u32,
+    pub creator: String,
+    pub expense: ExpenseTemplateJson,
+    pub period_blocks: u32,
+    pub remaining: u32,
+    pub paused: bool,
+    pub next_block: u32,
+}
+
+#[derive(Serialize)]
+pub struct BudgetJson {
+    pub category: Option<CategoryJson>,
+    pub currency: String,
+    pub limit: String,
+    pub period_blocks: u32,
+    pub period_start: u32,
+    pub spent: String,
+    pub thresholds: Vec<u8>,
+    pub reached: Vec<u8>,
+    pub exceeded: bool,
+}
+
+#[derive(Serialize)]
+pub struct ApprovalPolicyJson {
+    pub threshold: Option<String>,
+    pub required_approvals: u32,
+}
+
+#[derive(Serialize)]
+pub struct DisputeJson {
+    pub id: u32,
+    pub expense_id: u32,
+    pub opened_by: String,
+    pub reason: String,
+    pub status: DisputeStatusJson,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "snake_case", tag = "kind", content = "value")]
+pub enum DisputeStatusJson {
+    Open,
+    Upheld,
+    Amended(String),
+    Voided,
+}
+
+// Amount of an account, used for the treasury funds and the balances
+#[derive(Serialize)]
+pub struct AmountJson {
+    pub actor_id: String,
+    pub amount: String,
+}
+
+#[derive(Serialize)]
+pub struct PoolPaymentJson {
+    pub id: u32,
+    pub vendor: String,
+    pub amount: String,
+    pub description: String,
+    pub paid_by: String,
+}
+
+#[derive(Serialize)]
+pub struct WithdrawalJson {
+    pub id: u32,
+    pub group_id: u32,
+    pub proposer: String,
+    pub to: String,
+    pub amount: String,
+    pub approvals: Vec<String>,
+    pub expires_at: u32,
+    pub executed: bool,
+    pub description: Option<String>,
+}
+
+#[derive(Serialize)]
+pub struct ArchivedGroupJson {
+    pub id: u32,
+    pub name: String,
+    pub members: Vec<String>,
+    pub closed_at: u32,
+    pub expense_count: u32,
+    pub payment_count: u32,
+    pub totals: Vec<CategoryTotalJson>,
+    pub final_balances: Vec<AmountJson>,
+    pub settlement: Vec<TransferJson>,
+}
+
+#[derive(Serialize)]
+pub struct CategoryTotalJson {
+    pub category: CategoryJson,
+    pub currency: String,
+    pub total: String,
+}
+
+#[derive(Serialize)]
+pub struct TransferJson {
+    pub from: String,
+    pub to: String,
+    pub amount: String,
+}
+
+// Renders the whole state returned by the `Query` query
+pub fn state_json(state: &IoState) -> serde_json::Result<String> {
+    serde_json::to_string_pretty(&StateJson::from(state))
+}
+
+// Renders a single group, e.g. the reply of the `QueryGroup` query
+pub fn group_json(group: &Group) -> serde_json::Result<String> {
+    serde_json::to_string_pretty(&GroupJson::from(group))
+}
+
+fn actors(actors: &[sails_rs::ActorId]) -> Vec<String> {
+    actors.iter().map(format_actor_id).collect()
+}
+
+impl From<&IoState> for StateJson {
+    fn from(state: &IoState) -> Self {
+        Self {
+            schema_version: SCHEMA_VERSION,
+            groups: state.groups.iter().map(Into::into).collect(),
+            withdrawals: state.withdrawals.iter().map(Into::into).collect(),
+            archived_groups: state.archived_groups.iter().map(Into::into).collect(),
+        }
+    }
+}
+
+impl From<&Group> for GroupJson {
+    fn from(group: &Group) -> Self {
+        Self {
+            id: group.id,
+            name: group.name.clone(),
+            description: group.description.clone(),
+            avatar_uri: group.avatar_uri.clone(),
+            avatar_hash: group.avatar_hash.map(|hash| format!("0x{}", hex::encode(hash.as_bytes()))),
+            default_split_mode: (&group.default_split_mode).into(),
+            default_currency: group.default_currency.clone(),
+            admins: actors(&group.admins),
+            members: actors(&group.members),
+            expenses: group.expenses.iter().map(Into::into).collect(),
+            payments: group.payments.iter().map(Into::into).collect(),
+            recurring_expenses: group.recurring_expenses.iter().map(Into::into).collect(),
+            budgets: group.budgets.iter().map(Into::into).collect(),
+            approval_policy: (&group.approval_policy).into(),
+            disputes: group.disputes.iter().map(Into::into).collect(),
+            pool: group.pool.iter().map(Into::into).collect(),
+            pool_payments: group.pool_payments.iter().map(Into::into).collect(),
+            withdrawal_quorum: group.withdrawal_quorum,
+        }
+    }
+}
+
+impl From<&SplitMode> for SplitModeJson {
+    fn from(mode: &SplitMode) -> Self {
+        match mode {
+            SplitMode::Equal => SplitModeJson::Equal,
+            SplitMode::Percentage => SplitModeJson::Percentage,
+            SplitMode::Exact => SplitModeJson::Exact,
+        }
+    }
+}
+
+impl From<&ExpenseCategory> for CategoryJson {
+    fn from(category: &ExpenseCategory) -> Self {
+        match category {
+            ExpenseCategory::Food => CategoryJson::Food,
+            ExpenseCategory::Transport => CategoryJson::Transport,
+            ExpenseCategory::Lodging => CategoryJson::Lodging,
+            ExpenseCategory::Utilities => CategoryJson::Utilities,
+            ExpenseCategory::Entertainment => CategoryJson::Entertainment,
+            ExpenseCategory::Other(name) => CategoryJson::Other(name.clone()),
+        }
+    }
+}
+
+impl From<&Expense> for ExpenseJson {
+    fn from(expense: &Expense) -> Self {
+        Self {
+            id: expense.id,
+            description: expense.description.clone(),
+            amount: expense.amount.to_string(),
+            currency: expense.currency.clone(),
+            category: (&expense.category).into(),
+            tags: expense.tags.clone(),
+            payer: format_actor_id(&expense.actor_id),
+            participants: actors(&expense.participants),
+            status: match expense.status {
+                ExpenseStatus::Approved => ExpenseStatusJson::Approved,
+                ExpenseStatus::Pending => ExpenseStatusJson::Pending,
+                ExpenseStatus::Rejected => ExpenseStatusJson::Rejected,
+                ExpenseStatus::Voided => ExpenseStatusJson::Voided,
+            },
+            approvals: actors(&expense.approvals),
+            created_block: expense.created_block,
+            created_at: expense.created_at,
+        }
+    }
+}
+
+impl From<&ExpenseDto> for ExpenseTemplateJson {
+    fn from(expense: &ExpenseDto) -> Self {
+        Self {
+            description: expense.description.clone(),
+            amount: expense.amount.to_string(),
+            currency: expense.currency.clone(),
+            category: (&expense.category).into(),
+            tags: expense.tags.clone(),
+            participants: actors(&expense.participants),
+        }
+    }
+}
+
+impl From<&Payment> for PaymentJson {
+    fn from(payment: &Payment) -> Self {
+        Self {
+            id: payment.id,
+            from: format_actor_id(&payment.from),
+            to: format_actor_id(&payment.to),
+            amount: payment.amount.to_string(),
+            status: match payment.status {
+                PaymentStatus::Unconfirmed => PaymentStatusJson::Unconfirmed,
+                PaymentStatus::Confirmed => PaymentStatusJson::Confirmed,
+                PaymentStatus::Rejected => PaymentStatusJson::Rejected,
+            },
+            created_block: payment.created_block,
+            created_at: payment.created_at,
+        }
+    }
+}
+
+impl From<&RecurringExpense> for RecurringExpenseJson {
+    fn from(recurring: &RecurringExpense) -> Self {
+        Self {
+            id: recurring.id,
+            creator: format_actor_id(&recurring.creator),
+            expense: (&recurring.expense).into(),
+            period_blocks: recurring.period_blocks,
+            remaining: recurring.remaining,
+            paused: recurring.paused,
+            next_block: recurring.next_block,
+        }
+    }
+}
+
+impl From<&Budget> for BudgetJson {
+    fn from(budget: &Budget) -> Self {
+        Self {
+            category: budget.category.as_ref().map(Into::into),
+            currency: budget.currency.clone(),
+            limit: budget.limit.to_string(),
+            period_blocks: budget.period_blocks,
+            period_start: budget.period_start,
+            spent: budget.spent.to_string(),
+            thresholds: budget.thresholds.clone(),
+            reached: budget.reached.clone(),
+            exceeded: budget.exceeded,
+        }
+    }
+}
+
+impl From<&ApprovalPolicy> for ApprovalPolicyJson {
+    fn from(policy: &ApprovalPolicy) -> Self {
+        Self {
+            threshold: policy.threshold.map(|threshold| threshold.to_string()),
+            required_approvals: policy.required_approvals,
+        }
+    }
+}
+
+impl From<&Dispute> for DisputeJson {
+    fn from(dispute: &Dispute) -> Self {
+        Self {
+            id: dispute.id,
+            expense_id: dispute.expense_id,
+            opened_by: format_actor_id(&dispute.opened_by),
+            reason: dispute.reason.clone(),
+            status: match &dispute.status {
+                DisputeStatus::Open => DisputeStatusJson::Open,
+                DisputeStatus::Upheld => DisputeStatusJson::Upheld,
+                DisputeStatus::Amended(amount) => DisputeStatusJson::Amended(amount.to_string()),
+                DisputeStatus::Voided => DisputeStatusJson::Voided,
+            },
+        }
+    }
+}
+
+impl From<&PoolBalance> for AmountJson {
+    fn from(balance: &PoolBalance) -> Self {
+        Self {
+            actor_id: format_actor_id(&balance.actor_id),
+            amount: balance.amount.to_string(),
+        }
+    }
+}
+
+impl From<&Balance> for AmountJson {
+    fn from(balance: &Balance) -> Self {
+        Self {
+            actor_id: format_actor_id(&balance.actor_id),
+            amount: balance.amount.to_string(),
+        }
+    }
+}
+
+impl From<&PoolPayment> for PoolPaymentJson {
+    fn from(payment: &PoolPayment) -> Self {
+        Self {
+            id: payment.id,
+            vendor: format_actor_id(&payment.vendor),
+            amount: payment.amount.to_string(),
+            description: payment.description.clone(),
+            paid_by: format_actor_id(&payment.paid_by),
+        }
+    }
+}
+
+impl From<&WithdrawalProposal> for WithdrawalJson {
+    fn from(proposal: &WithdrawalProposal) -> Self {
+        Self {
+            id: proposal.id,
+            group_id: proposal.group_id,
+            proposer: format_actor_id(&proposal.proposer),
+            to: format_actor_id(&proposal.to),
+            amount: proposal.amount.to_string(),
+            approvals: actors(&proposal.approvals),
+            expires_at: proposal.expires_at,
+            executed: proposal.executed,
+            description: proposal.description.clone(),
+        }
+    }
+}
+
+impl From<&ArchivedGroup> for ArchivedGroupJson {
+    fn from(group: &ArchivedGroup) -> Self {
+        Self {
+            id: group.id,
+            name: group.name.clone(),
+            members: actors(&group.members),
+            closed_at: group.closed_at,
+            expense_count: group.expense_count,
+            payment_count: group.payment_count,
+            totals: group.totals.iter().map(Into::into).collect(),
+            final_balances: group.final_balances.iter().map(Into::into).collect(),
+            settlement: group.settlement.iter().map(Into::into).collect(),
+        }
+    }
+}
+
+impl From<&CategoryTotal> for CategoryTotalJson {
+    fn from(total: &CategoryTotal) -> Self {
+        Self {
+            category: (&total.category).into(),
+            currency: total.currency.clone(),
+            total: total.total.to_string(),
+        }
+    }
+}
+
+impl From<&Transfer> for TransferJson {
+    fn from(transfer: &Transfer) -> Self {
+        Self {
+            from: format_actor_id(&transfer.from),
+            to: format_actor_id(&transfer.to),
+            amount: transfer.amount.to_string(),
+        }
+    }
+}
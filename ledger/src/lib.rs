@@ -0,0 +1,37 @@
+// Host-side conversions of the group ledger to and from the formats used
+// outside the chain
+pub mod csv_export;
+pub mod csv_import;
+pub mod json;
+
+pub use csv_export::*;
+pub use csv_import::*;
+pub use json::*;
+
+use sails_rs::ActorId;
+
+pub fn format_actor_id(actor_id: &ActorId) -> String {
+    format!("0x{}", hex::encode(actor_id.as_ref()))
+}
+
+// Formats a block timestamp (milliseconds since the Unix epoch) as its UTC
+// date, e.g. `2024-01-05`. Entries restored from snapshots that did not store
+// the timestamp have 0, which is shown as an empty date
+pub fn format_date(timestamp_ms: u64) -> String {
+    if timestamp_ms == 0 {
+        return String::new();
+    }
+
+    // Days to civil date, from http://howardhinnant.github.io/date_algorithms.html
+    let days = timestamp_ms / 86_400_000 + 719_468;
+    let era = days / 146_097;
+    let day_of_era = days % 146_097;
+    let year_of_era = (day_of_era - day_of_era / 1_460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let month_index = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * month_index + 2) / 5 + 1;
+    let month = if month_index < 10 { month_index + 3 } else { month_index - 9 };
+    let year = year_of_era + era * 400 + u64::from(month <= 2);
+
+    format!("{year:04}-{month:02}-{day:02}")
+}
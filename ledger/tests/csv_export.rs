@@ -0,0 +1,143 @@
+use client::{
+    ApprovalPolicy, Dispute, DisputeStatus, Expense, ExpenseCategory, ExpenseStatus, Group, Payment,
+    PaymentStatus, SplitMode,
+};
+use ledger::{format_actor_id, format_date, group_ledger_csv};
+use sails_rs::ActorId;
+
+const ALICE: u64 = 1;
+const BOB: u64 = 2;
+
+fn group(expenses: Vec<Expense>, payments: Vec<Payment>, disputes: Vec<Dispute>) -> Group {
+    Group {
+        id: 1,
+        name: "Flat".to_owned(),
+        description: String::new(),
+        avatar_uri: String::new(),
+        avatar_hash: None,
+        default_split_mode: SplitMode::Equal,
+        default_currency: "VARA".to_owned(),
+        admins: vec![ALICE.into()],
+        members: vec![ALICE.into(), BOB.into()],
+        expenses,
+        payments,
+        recurring_expenses: vec![],
+        budgets: vec![],
+        approval_policy: ApprovalPolicy { threshold: None, required_approvals: 0 },
+        disputes,
+        pool: vec![],
+        pool_payments: vec![],
+        withdrawal_quorum: 0,
+    }
+}
+
+fn expense(id: u32, payer: u64, amount: u128, description: &str) -> Expense {
+    Expense {
+        id,
+        description: description.to_owned(),
+        amount,
+        currency: "VARA".to_owned(),
+        category: ExpenseCategory::Utilities,
+        tags: vec![],
+        actor_id: payer.into(),
+        participants: vec![ALICE.into(), BOB.into()],
+        status: ExpenseStatus::Approved,
+        approvals: vec![],
+        created_block: 0,
+        created_at: 0,
+    }
+}
+
+fn payment(id: u32, status: PaymentStatus) -> Payment {
+    Payment { id, from: BOB.into(), to: ALICE.into(), amount: 50, status, created_block: 0, created_at: 0 }
+}
+
+fn actor(id: u64) -> String {
+    format_actor_id(&ActorId::from(id))
+}
+
+#[test]
+fn renders_rows_with_running_balances() {
+    let mut first = expense(2, ALICE, 100, "Power, water");
+    // 2024-01-05 12:00 UTC
+    first.created_at = 1_704_456_000_000;
+
+    let group = group(
+        vec![first, expense(4, BOB, 30, "Internet")],
+        vec![payment(3, PaymentStatus::Confirmed)],
+        vec![],
+    );
+
+    let csv = group_ledger_csv(&group).unwrap();
+    let lines: Vec<&str> = csv.lines().collect();
+
+    let participants = format!("{};{}", actor(ALICE), actor(BOB));
+
+    assert_eq!(lines.len(), 4);
+    assert_eq!(
+        lines[0],
+        format!("date,type,id,status,payer,participants,amount,currency,category,description,{},{}", actor(ALICE), actor(BOB))
+    );
+    assert_eq!(
+        lines[1],
+        format!("2024-01-05,expense,2,approved,{},{participants},100,VARA,utilities,\"Power, water\",50,-50", actor(ALICE))
+    );
+    assert_eq!(lines[2], format!(",payment,3,confirmed,{},{},50,,,,0,0", actor(BOB), actor(ALICE)));
+    assert_eq!(
+        lines[3],
+        format!(",expense,4,approved,{},{participants},30,VARA,utilities,Internet,-15,15", actor(BOB))
+    );
+}
+
+#[test]
+fn disputed_and_pending_entries_keep_the_balances() {
+    let mut pending = expense(3, BOB, 40, "Cleaning");
+    pending.status = ExpenseStatus::Pending;
+
+    let group = group(
+        vec![expense(2, ALICE, 100, "Rent"), pending],
+        vec![payment(4, PaymentStatus::Unconfirmed)],
+        vec![Dispute {
+            id: 5,
+            expense_id: 2,
+            opened_by: BOB.into(),
+            reason: "Wrong amount".to_owned(),
+            status: DisputeStatus::Open,
+        }],
+    );
+
+    let csv = group_ledger_csv(&group).unwrap();
+    let rows: Vec<Vec<&str>> = csv.lines().skip(1).map(|l| l.split(',').collect()).collect();
+
+    assert_eq!(rows[0][3], "disputed");
+    assert_eq!(rows[1][3], "pending");
+    assert_eq!(rows[2][3], "unconfirmed");
+    assert!(rows.iter().all(|row| row[row.len() - 2..] == ["0", "0"]));
+}
+
+#[test]
+fn former_members_keep_their_balance() {
+    const CAROL: u64 = 3;
+
+    let mut shared = expense(2, CAROL, 90, "Groceries");
+    shared.participants.push(CAROL.into());
+
+    let group = group(vec![shared], vec![], vec![]);
+
+    let csv = group_ledger_csv(&group).unwrap();
+    let lines: Vec<&str> = csv.lines().collect();
+
+    assert!(lines[0].ends_with(&format!("{},{},{}", actor(ALICE), actor(BOB), actor(CAROL))));
+    assert!(lines[1].ends_with("-30,-30,60"));
+}
+
+#[test]
+fn formats_block_timestamps_as_utc_dates() {
+    assert_eq!(format_date(0), "");
+    assert_eq!(format_date(1), "1970-01-01");
+    // 2024-02-29 23:59:59.999 UTC
+    assert_eq!(format_date(1_709_251_199_999), "2024-02-29");
+    assert_eq!(format_date(1_709_251_200_000), "2024-03-01");
+    // 2000-12-31 00:00 UTC
+    assert_eq!(format_date(978_220_800_000), "2000-12-31");
+}
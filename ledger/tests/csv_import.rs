@@ -0,0 +1,112 @@
+use client::ExpenseCategory;
+use ledger::{import_splitwise_csv, parse_mapping, ImportError, ImportOptions, SkippedRow};
+use sails_rs::ActorId;
+use std::collections::BTreeMap;
+
+const MAPPING: &str = "name,account
+Ana,0x0000000000000000000000000000000000000000000000000000000000000001
+Ben,0x0000000000000000000000000000000000000000000000000000000000000002
+Cai,0x0000000000000000000000000000000000000000000000000000000000000003
+";
+
+const EXPORT: &str = "Date,Description,Category,Cost,Currency,Ana,Ben,Cai
+2021-03-01,Groceries,Groceries,30.00,EUR,20.00,-10.00,-10.00
+2021-03-02,Taxi,Taxi,10.00,EUR,-5.00,5.00,0.00
+2021-03-03,Settle up,Payment,10.00,EUR,10.00,-10.00,0.00
+2021-03-04,Dinner,Dining out,10.00,EUR,6.66,-3.33,-3.33
+2021-03-05,Concert,Music,40.00,EUR,30.00,-30.00,0.00
+2021-03-06,Hotel,Hotel,60.00,EUR,20.00,20.00,-40.00
+
+2021-03-07,Total balance,,,EUR,80.66,-33.33,-53.33
+";
+
+fn actor(id: u8) -> ActorId {
+    let mut bytes = [0; 32];
+    bytes[31] = id;
+    ActorId::from(bytes)
+}
+
+fn mapping() -> BTreeMap<String, ActorId> {
+    parse_mapping(MAPPING.as_bytes()).unwrap()
+}
+
+#[test]
+fn imports_equal_splits() {
+    let import = import_splitwise_csv(EXPORT.as_bytes(), &mapping(), &ImportOptions::default()).unwrap();
+
+    assert_eq!(import.expenses.len(), 3);
+
+    let groceries = &import.expenses[0];
+    assert_eq!(groceries.payer, actor(1));
+    assert_eq!(groceries.expense.amount, 3000);
+    assert_eq!(groceries.expense.currency, "EUR");
+    assert_eq!(groceries.expense.description, "Groceries");
+    assert_eq!(groceries.expense.category, ExpenseCategory::Food);
+    assert_eq!(groceries.expense.tags, vec!["2021-03-01".to_owned()]);
+    assert_eq!(groceries.expense.participants.len(), 3);
+
+    // The payer does not take part in the taxi
+    let taxi = &import.expenses[1];
+    assert_eq!(taxi.payer, actor(2));
+    assert_eq!(taxi.expense.participants, vec![actor(1), actor(2)]);
+    assert_eq!(taxi.expense.category, ExpenseCategory::Transport);
+
+    // The remainder of the split goes to the payer, who is listed first
+    let dinner = &import.expenses[2];
+    assert_eq!(dinner.expense.amount, 1000);
+    assert_eq!(dinner.expense.participants[0], actor(1));
+}
+
+#[test]
+fn reports_rows_that_cannot_be_imported() {
+    let import = import_splitwise_csv(EXPORT.as_bytes(), &mapping(), &ImportOptions::default()).unwrap();
+
+    assert_eq!(import.skipped, vec![
+        SkippedRow { line: 4, reason: "Payments between members are not imported".to_owned() },
+        SkippedRow { line: 6, reason: "Unequal splits are not supported".to_owned() },
+        SkippedRow { line: 7, reason: "Expense must have a single payer".to_owned() },
+    ]);
+}
+
+#[test]
+fn groups_batches_by_payer() {
+    let import = import_splitwise_csv(EXPORT.as_bytes(), &mapping(), &ImportOptions::default()).unwrap();
+    let batches = import.batches(1);
+
+    assert_eq!(batches.len(), 3);
+    assert_eq!(batches.iter().filter(|(payer, _)| *payer == actor(1)).count(), 2);
+    assert!(batches.iter().all(|(_, expenses)| expenses.len() == 1));
+
+    let batches = import.batches(50);
+    assert_eq!(batches.len(), 2);
+    assert_eq!(batches[0], (actor(1), vec![import.expenses[0].expense.clone(), import.expenses[2].expense.clone()]));
+}
+
+#[test]
+fn rejects_members_missing_from_the_mapping() {
+    let export = "Date,Description,Category,Cost,Currency,Ana,Dee\n";
+
+    let error = import_splitwise_csv(export.as_bytes(), &mapping(), &ImportOptions::default()).unwrap_err();
+
+    assert!(matches!(error, ImportError::UnknownMember(name) if name == "Dee"));
+}
+
+#[test]
+fn maps_categories_by_word() {
+    let export = "Date,Description,Category,Cost,Currency,Ana,Ben
+2021-03-01,Heating,Heat/gas,10.00,EUR,5.00,-5.00
+2021-03-02,Fuel,Gas/fuel,10.00,EUR,5.00,-5.00
+2021-03-03,Bus,Bus/train,10.00,EUR,5.00,-5.00
+2021-03-04,Trip,Business,10.00,EUR,5.00,-5.00
+";
+
+    let import = import_splitwise_csv(export.as_bytes(), &mapping(), &ImportOptions::default()).unwrap();
+    let categories: Vec<_> = import.expenses.iter().map(|e| e.expense.category.clone()).collect();
+
+    assert_eq!(categories, vec![
+        ExpenseCategory::Utilities,
+        ExpenseCategory::Transport,
+        ExpenseCategory::Transport,
+        ExpenseCategory::Other("Business".to_owned()),
+    ]);
+}
@@ -0,0 +1,115 @@
+use client::{
+    ApprovalPolicy, Dispute, DisputeStatus, Expense, ExpenseCategory, ExpenseStatus, Group, IoState, Payment,
+    PaymentStatus, SplitMode,
+};
+use ledger::{format_actor_id, group_json, state_json, SCHEMA_VERSION};
+use sails_rs::{ActorId, H256};
+use serde_json::{json, Value};
+
+const ALICE: u64 = 1;
+const BOB: u64 = 2;
+
+fn group() -> Group {
+    Group {
+        id: 1,
+        name: "Flat".to_owned(),
+        description: String::new(),
+        avatar_uri: String::new(),
+        avatar_hash: Some(H256::repeat_byte(0xab)),
+        default_split_mode: SplitMode::Equal,
+        default_currency: "VARA".to_owned(),
+        admins: vec![ALICE.into()],
+        members: vec![ALICE.into(), BOB.into()],
+        expenses: vec![Expense {
+            id: 2,
+            description: "Gym".to_owned(),
+            amount: u128::MAX,
+            currency: "VARA".to_owned(),
+            category: ExpenseCategory::Other("Sport".to_owned()),
+            tags: vec![],
+            actor_id: ALICE.into(),
+            participants: vec![ALICE.into(), BOB.into()],
+            status: ExpenseStatus::Pending,
+            approvals: vec![BOB.into()],
+            created_block: 12,
+            created_at: 1_704_456_000_000,
+        }],
+        payments: vec![Payment {
+            id: 3,
+            from: BOB.into(),
+            to: ALICE.into(),
+            amount: 50,
+            status: PaymentStatus::Confirmed,
+            created_block: 13,
+            created_at: 1_704_456_003_000,
+        }],
+        recurring_expenses: vec![],
+        budgets: vec![],
+        approval_policy: ApprovalPolicy { threshold: Some(1_000), required_approvals: 1 },
+        disputes: vec![Dispute {
+            id: 4,
+            expense_id: 2,
+            opened_by: BOB.into(),
+            reason: "Too much".to_owned(),
+            status: DisputeStatus::Amended(20),
+        }],
+        pool: vec![],
+        pool_payments: vec![],
+        withdrawal_quorum: 0,
+    }
+}
+
+fn actor(id: u64) -> String {
+    format_actor_id(&ActorId::from(id))
+}
+
+#[test]
+fn renders_group_with_hex_accounts_and_string_amounts() {
+    let value: Value = serde_json::from_str(&group_json(&group()).unwrap()).unwrap();
+
+    assert_eq!(value["avatar_hash"], json!(format!("0x{}", "ab".repeat(32))));
+    assert_eq!(value["default_split_mode"], json!("equal"));
+    assert_eq!(value["members"], json!([actor(ALICE), actor(BOB)]));
+    assert_eq!(
+        value["expenses"][0],
+        json!({
+            "id": 2,
+            "description": "Gym",
+            "amount": u128::MAX.to_string(),
+            "currency": "VARA",
+            "category": { "kind": "other", "value": "Sport" },
+            "tags": [],
+            "payer": actor(ALICE),
+            "participants": [actor(ALICE), actor(BOB)],
+            "status": "pending",
+            "approvals": [actor(BOB)],
+            "created_block": 12,
+            "created_at": 1_704_456_000_000u64,
+        })
+    );
+    assert_eq!(
+        value["payments"][0],
+        json!({
+            "id": 3,
+            "from": actor(BOB),
+            "to": actor(ALICE),
+            "amount": "50",
+            "status": "confirmed",
+            "created_block": 13,
+            "created_at": 1_704_456_003_000u64,
+        })
+    );
+    assert_eq!(value["approval_policy"], json!({ "threshold": "1000", "required_approvals": 1 }));
+    assert_eq!(value["disputes"][0]["status"], json!({ "kind": "amended", "value": "20" }));
+}
+
+#[test]
+fn renders_state_with_schema_version() {
+    let state = IoState { groups: vec![group()], withdrawals: vec![], archived_groups: vec![] };
+    let value: Value = serde_json::from_str(&state_json(&state).unwrap()).unwrap();
+
+    assert_eq!(value["schema_version"], json!(SCHEMA_VERSION));
+    assert_eq!(value["groups"][0]["id"], json!(1));
+    assert_eq!(value["withdrawals"], json!([]));
+    assert_eq!(value["archived_groups"], json!([]));
+}
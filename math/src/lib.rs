@@ -0,0 +1,107 @@
+#![no_std]
+
+// Arithmetic of the group ledger shared by the program and the crates that
+// rebuild its state off-chain (indexer, ledger), so they all get the same
+// balances and treasury. The functions are generic over the account type
+extern crate alloc;
+
+use alloc::vec::Vec;
+
+// Entry of the ledger that moves the balances
+pub enum Movement<'a, A> {
+    // Expense paid by `payer` and split equally between the participants
+    Expense { payer: A, amount: u128, participants: &'a [A] },
+    // Payment from `from` to `to`, it cancels a debt of `from`
+    Payment { from: A, to: A, amount: u128 },
+}
+
+// Splits an amount equally between the participants, the remainder of the
+// division is assigned to the first participants
+pub fn split_equally<A: Copy>(amount: u128, participants: &[A]) -> Vec<(A, u128)> {
+    let count = participants.len() as u128;
+    if count == 0 {
+        return Vec::new();
+    }
+
+    let share = amount / count;
+    let remainder = amount % count;
+
+    participants
+        .iter()
+        .enumerate()
+        .map(|(index, participant)| {
+            let extra = if (index as u128) < remainder { 1 } else { 0 };
+            (*participant, share + extra)
+        })
+        .collect()
+}
+
+// Balances of the members after the movements, positive when the member is
+// owed money. Accounts that are not members (e.g. they left the group) are
+// added after the members. Panics if a balance overflows, the program keeps
+// the sum of its ledger within an i128 so it can not happen there
+pub fn balances<'a, A: Copy + PartialEq + 'a>(
+    members: &[A],
+    movements: impl IntoIterator<Item = Movement<'a, A>>,
+) -> Vec<(A, i128)> {
+    let mut balances: Vec<(A, i128)> = members.iter().map(|member| (*member, 0)).collect();
+
+    for movement in movements {
+        match movement {
+            Movement::Expense { payer, amount, participants } => {
+                add_to_balance(&mut balances, payer, amount as i128);
+
+                for (participant, share) in split_equally(amount, participants) {
+                    add_to_balance(&mut balances, participant, -(share as i128));
+                }
+            }
+            Movement::Payment { from, to, amount } => {
+                add_to_balance(&mut balances, from, amount as i128);
+                add_to_balance(&mut balances, to, -(amount as i128));
+            }
+        }
+    }
+
+    balances
+}
+
+// Adds an amount to the balance of an account, the account is added when it
+// has no balance yet
+pub fn add_to_balance<A: PartialEq>(balances: &mut Vec<(A, i128)>, account: A, amount: i128) {
+    match balances.iter_mut().find(|(a, _)| *a == account) {
+        Some((_, balance)) => {
+            *balance = balance
+                .checked_add(amount)
+                .expect("Balance overflow");
+        }
+        None => balances.push((account, amount)),
+    }
+}
+
+// Takes an amount from the funds of a treasury, every account pays a part
+// proportional to its funds and the remainder of the divisions is taken one
+// unit at a time from the accounts that still have funds. The amount must not
+// be greater than the sum of the funds
+pub fn debit_pro_rata(funds: &mut [&mut u128], amount: u128) {
+    let total = funds.iter().map(|f| **f).sum::<u128>();
+    if total == 0 {
+        return;
+    }
+
+    let mut debited = 0;
+    for fund in funds.iter_mut() {
+        let part = fund.saturating_mul(amount) / total;
+        **fund -= part;
+        debited += part;
+    }
+
+    let mut remainder = amount - debited;
+    for fund in funds.iter_mut() {
+        if remainder == 0 {
+            break;
+        }
+        let part = remainder.min(**fund).min(1);
+        **fund -= part;
+        remainder -= part;
+    }
+}
@@ -0,0 +1,28 @@
+use math::{balances, debit_pro_rata, split_equally, Movement};
+
+#[test]
+fn split_gives_the_remainder_to_the_first_participants() {
+    assert_eq!(split_equally(10, &[1, 2, 3]), vec![(1, 4), (2, 3), (3, 3)]);
+    assert!(split_equally(10, &[] as &[u8]).is_empty());
+}
+
+#[test]
+fn balances_add_expenses_and_payments() {
+    let balances = balances(&[1, 2, 3], [
+        Movement::Expense { payer: 1, amount: 90, participants: &[1, 2, 3] },
+        Movement::Payment { from: 2, to: 1, amount: 30 },
+        Movement::Expense { payer: 4, amount: 10, participants: &[3] },
+    ]);
+
+    assert_eq!(balances, vec![(1, 30), (2, 0), (3, -40), (4, 10)]);
+}
+
+#[test]
+fn debit_is_proportional_to_the_funds() {
+    let (mut a, mut b, mut c) = (50, 30, 20);
+
+    debit_pro_rata(&mut [&mut a, &mut b, &mut c], 11);
+
+    // 5.5, 3.3 and 2.2 are rounded down and the 1 left is taken from the first
+    assert_eq!((a, b, c), (44, 27, 18));
+}
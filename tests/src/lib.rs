@@ -0,0 +1,82 @@
+// Helpers to deploy the program in gtest and talk to it as different actors
+use client::{traits::AppFactory as _, AppFactory, Config, Service};
+use sails_rs::{calls::*, gtest::{calls::GTestRemoting, System}, ActorId, CodeId};
+
+pub const ADMIN: u64 = 42;
+pub const ALICE: u64 = 43;
+pub const BOB: u64 = 44;
+pub const CAROL: u64 = 45;
+
+pub const ACTORS: [u64; 4] = [ADMIN, ALICE, BOB, CAROL];
+
+const INITIAL_BALANCE: u128 = 100_000_000_000_000_000;
+
+pub struct Fixture {
+    remoting: GTestRemoting,
+    pub code_id: CodeId,
+    pub program_id: ActorId,
+}
+
+impl Fixture {
+    // Deploys the program with the default constructor, `ADMIN` is the
+    // program admin
+    pub async fn new() -> Self {
+        let (remoting, code_id) = Self::setup();
+
+        let program_id = AppFactory::new(remoting.clone())
+            .new()
+            .send_recv(code_id, b"salt")
+            .await
+            .expect("Failed to deploy the program");
+
+        Self { remoting, code_id, program_id }
+    }
+
+    // Deploys the program with the parameters of the instance, the config is
+    // sent by `ADMIN`
+    pub async fn with_config(config: Config) -> Self {
+        let (remoting, code_id) = Self::setup();
+
+        let program_id = AppFactory::new(remoting.clone())
+            .new_with_config(config)
+            .send_recv(code_id, b"salt")
+            .await
+            .expect("Failed to deploy the program");
+
+        Self { remoting, code_id, program_id }
+    }
+
+    // Starts the system with funds for every actor and uploads the code
+    fn setup() -> (GTestRemoting, CodeId) {
+        let system = System::new();
+        system.init_logger();
+
+        for actor in ACTORS {
+            system.mint_to(actor, INITIAL_BALANCE);
+        }
+
+        let code_id = system.submit_code(wasm::WASM_BINARY);
+
+        (GTestRemoting::new(system, ADMIN.into()), code_id)
+    }
+
+    // Client of the service that sends the messages as `actor`
+    pub fn service(&self, actor: u64) -> Service<GTestRemoting> {
+        Service::new(self.remoting_as(actor))
+    }
+
+    pub fn remoting(&self) -> &GTestRemoting {
+        &self.remoting
+    }
+
+    // Value held by the program, the gas paid by the senders is not part of it
+    pub fn program_balance(&self) -> u128 {
+        self.remoting.system().balance_of(self.program_id)
+    }
+
+    // Remoting that sends the messages as `actor`, for the crates that build
+    // their own clients on top of it
+    pub fn remoting_as(&self, actor: u64) -> GTestRemoting {
+        self.remoting.clone().with_actor_id(actor.into())
+    }
+}
@@ -0,0 +1,126 @@
+use client::{traits::Service as _, DisputeResolution, Events, ExpenseCategory, ExpenseDto, ExpenseStatus};
+use futures::{FutureExt as _, StreamExt as _};
+use indexer::Indexer;
+use sails_rs::{calls::*, events::Listener as _, ActorId};
+use tests::{Fixture, ADMIN, ALICE, BOB};
+
+// One VARA, values sent from the program must not be below the existential
+// deposit
+const UNIT: u128 = 1_000_000_000_000;
+
+fn expense_dto(amount: u128, participants: &[u64]) -> ExpenseDto {
+    ExpenseDto {
+        description: "Dinner".to_owned(),
+        amount,
+        currency: "VARA".to_owned(),
+        category: ExpenseCategory::Food,
+        tags: vec![],
+        participants: participants.iter().map(|p| ActorId::from(*p)).collect(),
+    }
+}
+
+// The events emitted by the program while the group is used are fed to the
+// indexer, which must end with the same view as the queries of the program
+#[tokio::test]
+async fn indexer_matches_the_program_state() {
+    let fixture = Fixture::new().await;
+    let program_id = fixture.program_id;
+    let mut remoting = fixture.remoting().clone();
+    let mut events = remoting.listen().await.unwrap();
+
+    let reply = fixture.service(ADMIN).create_group("Trip".to_owned()).send_recv(program_id).await.unwrap();
+    let Events::GroupCreated(group_id) = reply else {
+        panic!("Unexpected reply: {reply:?}");
+    };
+
+    for member in [ALICE, BOB] {
+        fixture.service(member).join_group(group_id).send_recv(program_id).await.unwrap();
+    }
+
+    let reply = fixture.service(ALICE)
+        .add_expense(group_id, expense_dto(300, &[]))
+        .send_recv(program_id)
+        .await
+        .unwrap();
+    let Events::ExpenseAdded((_, disputed_id)) = reply else {
+        panic!("Unexpected reply: {reply:?}");
+    };
+    let reply = fixture.service(BOB)
+        .add_expense(group_id, expense_dto(101, &[ALICE, BOB]))
+        .send_recv(program_id)
+        .await
+        .unwrap();
+    let Events::ExpenseAdded((_, open_id)) = reply else {
+        panic!("Unexpected reply: {reply:?}");
+    };
+
+    fixture.service(ALICE).add_payment(group_id, 20, BOB.into()).send_recv(program_id).await.unwrap();
+    let payments = fixture.service(BOB).query_payments(group_id).recv(program_id).await.unwrap().unwrap();
+    fixture.service(BOB).confirm_payment(group_id, payments[0].id).send_recv(program_id).await.unwrap();
+
+    let reply = fixture.service(BOB)
+        .open_dispute(group_id, disputed_id, "Too much".to_owned())
+        .send_recv(program_id)
+        .await
+        .unwrap();
+    let Events::DisputeOpened((_, dispute_id)) = reply else {
+        panic!("Unexpected reply: {reply:?}");
+    };
+
+    // The amount is raised over the approval threshold, so the expense goes
+    // back to pending and leaves the balances
+    fixture.service(ADMIN).set_approval_policy(group_id, Some(500), 1).send_recv(program_id).await.unwrap();
+    let reply = fixture.service(ADMIN)
+        .resolve_dispute(group_id, dispute_id, DisputeResolution::Amend(900))
+        .send_recv(program_id)
+        .await
+        .unwrap();
+    assert_eq!(reply, Events::DisputeResolved((group_id, dispute_id)));
+
+    fixture.service(ALICE)
+        .open_dispute(group_id, open_id, "Not mine".to_owned())
+        .send_recv(program_id)
+        .await
+        .unwrap();
+
+    for (member, value) in [(ALICE, 7 * UNIT), (BOB, 3 * UNIT)] {
+        fixture.service(member).deposit(group_id).with_value(value).send_recv(program_id).await.unwrap();
+    }
+    let reply = fixture.service(ADMIN)
+        .pay_from_pool(group_id, ADMIN.into(), 5 * UNIT, "Tickets".to_owned())
+        .send_recv(program_id)
+        .await
+        .unwrap();
+    assert_eq!(reply, Events::PoolPaid((group_id, 5 * UNIT)));
+
+    let mut indexer = Indexer::new();
+    let mut ingested = 0;
+    while let Some(Some((source, payload))) = events.next().now_or_never() {
+        if source == program_id {
+            indexer.ingest(&payload).unwrap();
+            ingested += 1;
+        }
+    }
+    assert!(ingested > 0);
+
+    let service = fixture.service(ADMIN);
+    let view = &indexer.groups[&group_id];
+
+    let members = service.query_group_members(group_id).recv(program_id).await.unwrap().unwrap();
+    assert_eq!(view.members, members);
+
+    let balances = service.query_balances(group_id).recv(program_id).await.unwrap().unwrap();
+    assert_eq!(view.balances(), balances);
+
+    let pool = service.query_pool(group_id).recv(program_id).await.unwrap().unwrap();
+    assert_eq!(view.pool, pool);
+    assert_eq!(view.pool_total(), 5 * UNIT);
+
+    let expenses = service.query_expenses(group_id).recv(program_id).await.unwrap().unwrap();
+    assert_eq!(view.expenses, expenses);
+    assert!(!view.disputed.contains(&disputed_id));
+    assert!(view.disputed.contains(&open_id));
+
+    let amended = view.expenses.iter().find(|e| e.id == disputed_id).unwrap();
+    assert_eq!((amended.amount, &amended.status), (900, &ExpenseStatus::Pending));
+}
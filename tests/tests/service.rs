@@ -0,0 +1,1572 @@
+use client::{
+    traits::{AppFactory as _, Service as _},
+    AppFactory, Config, DisputeResolution, Events, ExpenseCategory, ExpenseDto, ExpenseStatus, FeeSettings, GroupAction,
+    GroupMetadataDto, LimitError, Limits, PaymentStatus, SplitMode, StatementEntry,
+};
+use sails_rs::{calls::*, ActorId};
+use tests::{Fixture, ADMIN, ALICE, BOB, CAROL};
+
+fn expense_dto(amount: u128, participants: &[u64]) -> ExpenseDto {
+    ExpenseDto {
+        description: "Groceries".to_owned(),
+        amount,
+        currency: "VARA".to_owned(),
+        category: ExpenseCategory::Food,
+        tags: vec!["market".to_owned()],
+        participants: participants.iter().map(|p| ActorId::from(*p)).collect(),
+    }
+}
+
+// Creates a group as `ADMIN` that `ALICE` and `BOB` join, returns its id
+async fn group_with_members(fixture: &Fixture) -> u32 {
+    let reply = fixture.service(ADMIN)
+        .create_group("Trip".to_owned())
+        .send_recv(fixture.program_id)
+        .await
+        .unwrap();
+
+    let Events::GroupCreated(group_id) = reply else {
+        panic!("Unexpected reply: {reply:?}");
+    };
+
+    for member in [ALICE, BOB] {
+        let reply = fixture.service(member)
+            .join_group(group_id)
+            .send_recv(fixture.program_id)
+            .await
+            .unwrap();
+
+        assert_eq!(reply, Events::UserJoined((member.into(), group_id)));
+    }
+
+    group_id
+}
+
+async fn add_expense(fixture: &Fixture, actor: u64, group_id: u32, expense: ExpenseDto) -> Events {
+    fixture.service(actor)
+        .add_expense(group_id, expense)
+        .send_recv(fixture.program_id)
+        .await
+        .unwrap()
+}
+
+async fn balance_of(fixture: &Fixture, group_id: u32, actor: u64) -> i128 {
+    fixture.service(actor)
+        .query_balances(group_id)
+        .recv(fixture.program_id)
+        .await
+        .unwrap()
+        .expect("Group not found")
+        .iter()
+        .find(|b| b.actor_id == actor.into())
+        .map(|b| b.amount)
+        .unwrap_or_default()
+}
+
+#[tokio::test]
+async fn create_group_works() {
+    let fixture = Fixture::new().await;
+    let mut service = fixture.service(ADMIN);
+
+    let reply = service.create_group("Trip".to_owned()).send_recv(fixture.program_id).await.unwrap();
+    let Events::GroupCreated(group_id) = reply else {
+        panic!("Unexpected reply: {reply:?}");
+    };
+
+    let group = service.query_group(group_id).recv(fixture.program_id).await.unwrap();
+    assert_eq!(group.name, "Trip");
+    assert_eq!(group.admins, vec![ActorId::from(ADMIN)]);
+    assert_eq!(group.members, vec![ActorId::from(ADMIN)]);
+
+    let groups = service.query_actor_groups().recv(fixture.program_id).await.unwrap();
+    assert_eq!(groups.len(), 1);
+
+    let groups = fixture.service(ALICE).query_actor_groups().recv(fixture.program_id).await.unwrap();
+    assert!(groups.is_empty());
+
+    let state = service.query().recv(fixture.program_id).await.unwrap();
+    assert_eq!(state.groups.len(), 1);
+}
+
+#[tokio::test]
+async fn create_group_fails_with_long_name() {
+    let fixture = Fixture::new().await;
+
+    let reply = fixture.service(ADMIN)
+        .create_group("a".repeat(65))
+        .send_recv(fixture.program_id)
+        .await
+        .unwrap();
+
+    assert_eq!(reply, Events::LimitExceeded(LimitError::NameTooLong));
+}
+
+#[tokio::test]
+async fn join_group_works() {
+    let fixture = Fixture::new().await;
+    let group_id = group_with_members(&fixture).await;
+
+    let members = fixture.service(CAROL)
+        .query_group_members(group_id)
+        .recv(fixture.program_id)
+        .await
+        .unwrap();
+
+    assert_eq!(members, Some(vec![ADMIN.into(), ALICE.into(), BOB.into()]));
+}
+
+#[tokio::test]
+async fn join_group_fails() {
+    let fixture = Fixture::new().await;
+    let group_id = group_with_members(&fixture).await;
+    let mut service = fixture.service(ALICE);
+
+    let reply = service.join_group(group_id).send_recv(fixture.program_id).await.unwrap();
+    assert_eq!(reply, Events::Error("User already in group".to_owned()));
+
+    let reply = service.join_group(group_id + 100).send_recv(fixture.program_id).await.unwrap();
+    assert_eq!(reply, Events::Error("Group not found".to_owned()));
+}
+
+#[tokio::test]
+async fn add_expense_works() {
+    let fixture = Fixture::new().await;
+    let group_id = group_with_members(&fixture).await;
+
+    let reply = add_expense(&fixture, ALICE, group_id, expense_dto(300, &[])).await;
+    let Events::ExpenseAdded((reply_group_id, expense_id)) = reply else {
+        panic!("Unexpected reply: {reply:?}");
+    };
+    assert_eq!(reply_group_id, group_id);
+
+    let expenses = fixture.service(BOB)
+        .query_expenses(group_id)
+        .recv(fixture.program_id)
+        .await
+        .unwrap()
+        .unwrap();
+
+    assert_eq!(expenses.len(), 1);
+    assert_eq!(expenses[0].id, expense_id);
+    assert_eq!(expenses[0].actor_id, ALICE.into());
+    assert_eq!(expenses[0].status, ExpenseStatus::Approved);
+    // Without participants the expense is split between all the members
+    assert_eq!(expenses[0].participants.len(), 3);
+
+    assert_eq!(balance_of(&fixture, group_id, ALICE).await, 200);
+    assert_eq!(balance_of(&fixture, group_id, BOB).await, -100);
+    assert_eq!(balance_of(&fixture, group_id, ADMIN).await, -100);
+
+    let totals = fixture.service(BOB)
+        .query_category_totals(group_id)
+        .recv(fixture.program_id)
+        .await
+        .unwrap()
+        .unwrap();
+
+    assert_eq!(totals.len(), 1);
+    assert_eq!(totals[0].category, ExpenseCategory::Food);
+    assert_eq!(totals[0].total, 300);
+}
+
+#[tokio::test]
+async fn add_expense_fails() {
+    let fixture = Fixture::new().await;
+    let group_id = group_with_members(&fixture).await;
+
+    let reply = add_expense(&fixture, ALICE, group_id, expense_dto(100, &[ALICE, CAROL])).await;
+    assert_eq!(reply, Events::Error("Participants must be members of the group".to_owned()));
+
+    let mut expense = expense_dto(100, &[]);
+    expense.tags = vec!["tag".to_owned(); 9];
+    let reply = add_expense(&fixture, ALICE, group_id, expense).await;
+    assert_eq!(reply, Events::LimitExceeded(LimitError::TooManyTags));
+
+    let reply = add_expense(&fixture, ALICE, group_id + 100, expense_dto(100, &[])).await;
+    assert_eq!(reply, Events::Error("Group not found".to_owned()));
+
+    let expenses = fixture.service(ALICE)
+        .query_expenses(group_id)
+        .recv(fixture.program_id)
+        .await
+        .unwrap()
+        .unwrap();
+    assert!(expenses.is_empty());
+}
+
+// An expense of someone outside the group would put a debt on every member
+#[tokio::test]
+async fn outsiders_cannot_add_expenses() {
+    let fixture = Fixture::new().await;
+    let group_id = group_with_members(&fixture).await;
+    let mut carol = fixture.service(CAROL);
+
+    let reply = add_expense(&fixture, CAROL, group_id, expense_dto(300, &[])).await;
+    assert_eq!(reply, Events::Error("User is not a member of the group".to_owned()));
+
+    let reply = carol
+        .add_expenses_batch(group_id, vec![expense_dto(300, &[ALICE, BOB])])
+        .send_recv(fixture.program_id)
+        .await
+        .unwrap();
+    assert_eq!(reply, Events::Error("User is not a member of the group".to_owned()));
+
+    let reply = carol
+        .batch(group_id, vec![GroupAction::AddExpense(expense_dto(300, &[]))])
+        .send_recv(fixture.program_id)
+        .await
+        .unwrap();
+    assert_eq!(reply, Events::Error("User is not a member of the group".to_owned()));
+
+    let reply = carol
+        .create_recurring_expense(group_id, expense_dto(300, &[]), PERIOD, 2)
+        .send_recv(fixture.program_id)
+        .await
+        .unwrap();
+    assert_eq!(reply, Events::Error("User is not a member of the group".to_owned()));
+
+    run_blocks(&fixture, PERIOD);
+    assert_eq!(expense_count(&fixture, group_id).await, 0);
+    assert_eq!(balance_of(&fixture, group_id, ALICE).await, 0);
+}
+
+#[tokio::test]
+async fn group_keeps_one_currency() {
+    let fixture = Fixture::new().await;
+    let group_id = group_with_members(&fixture).await;
+
+    let reply = add_expense(&fixture, ALICE, group_id, expense_dto(300, &[])).await;
+    assert!(matches!(reply, Events::ExpenseAdded(..)));
+
+    // The first expense sets the currency of the group
+    let mut expense = expense_dto(100, &[]);
+    expense.currency = "USD".to_owned();
+    let reply = add_expense(&fixture, ALICE, group_id, expense).await;
+    assert_eq!(reply, Events::Error("Currency does not match the group currency".to_owned()));
+
+    // The balances must fit in an i128
+    let reply = add_expense(&fixture, ALICE, group_id, expense_dto(u128::MAX, &[])).await;
+    assert_eq!(reply, Events::Error("Amount is too large".to_owned()));
+
+    let expenses = fixture.service(ALICE)
+        .query_expenses(group_id)
+        .recv(fixture.program_id)
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(expenses.len(), 1);
+}
+
+#[tokio::test]
+async fn category_totals_skip_pending_expenses() {
+    let fixture = Fixture::new().await;
+    let group_id = group_with_members(&fixture).await;
+
+    let reply = fixture.service(ADMIN)
+        .set_approval_policy(group_id, Some(500), 1)
+        .send_recv(fixture.program_id)
+        .await
+        .unwrap();
+    assert_eq!(reply, Events::ApprovalPolicySet(group_id));
+
+    add_expense(&fixture, ALICE, group_id, expense_dto(300, &[])).await;
+    add_expense(&fixture, ALICE, group_id, expense_dto(900, &[])).await;
+
+    let totals = fixture.service(BOB)
+        .query_category_totals(group_id)
+        .recv(fixture.program_id)
+        .await
+        .unwrap()
+        .unwrap();
+
+    assert_eq!(totals.len(), 1);
+    assert_eq!(totals[0].total, 300);
+}
+
+// Amount spent in the first budget of the group
+async fn spent(fixture: &Fixture, group_id: u32) -> u128 {
+    fixture.service(ADMIN)
+        .query_budget_status(group_id)
+        .recv(fixture.program_id)
+        .await
+        .unwrap()
+        .unwrap()[0]
+        .spent
+}
+
+// Opens a dispute on the expense as `BOB`, returns its id
+async fn open_dispute(fixture: &Fixture, group_id: u32, expense_id: u32) -> u32 {
+    let reply = fixture.service(BOB)
+        .open_dispute(group_id, expense_id, "Wrong amount".to_owned())
+        .send_recv(fixture.program_id)
+        .await
+        .unwrap();
+    let Events::DisputeOpened((_, dispute_id)) = reply else {
+        panic!("Unexpected reply: {reply:?}");
+    };
+    dispute_id
+}
+
+// Resolves the dispute as `ADMIN`
+async fn resolve_dispute(fixture: &Fixture, group_id: u32, dispute_id: u32, resolution: DisputeResolution) -> Events {
+    fixture.service(ADMIN)
+        .resolve_dispute(group_id, dispute_id, resolution)
+        .send_recv(fixture.program_id)
+        .await
+        .unwrap()
+}
+
+// Opens a dispute on the expense as `BOB` and resolves it as `ADMIN`
+async fn resolve(fixture: &Fixture, group_id: u32, expense_id: u32, resolution: DisputeResolution) -> Events {
+    let dispute_id = open_dispute(fixture, group_id, expense_id).await;
+    resolve_dispute(fixture, group_id, dispute_id, resolution).await
+}
+
+#[tokio::test]
+async fn resolved_disputes_update_the_budget() {
+    let fixture = Fixture::new().await;
+    let group_id = group_with_members(&fixture).await;
+    let mut admin = fixture.service(ADMIN);
+
+    let reply = admin
+        .set_budget(group_id, Some(ExpenseCategory::Food), "VARA".to_owned(), 1_000, 1_000, vec![50])
+        .send_recv(fixture.program_id)
+        .await
+        .unwrap();
+    assert_eq!(reply, Events::BudgetSet(group_id));
+
+    let Events::ExpenseAdded((_, lunch_id)) = add_expense(&fixture, ALICE, group_id, expense_dto(300, &[])).await else {
+        panic!("Expense not added");
+    };
+    let Events::ExpenseAdded((_, dinner_id)) = add_expense(&fixture, ALICE, group_id, expense_dto(400, &[])).await else {
+        panic!("Expense not added");
+    };
+
+    assert_eq!(spent(&fixture, group_id).await, 700);
+
+    let reply = resolve(&fixture, group_id, lunch_id, DisputeResolution::Void).await;
+    assert!(matches!(reply, Events::DisputeResolved(_)));
+    assert_eq!(spent(&fixture, group_id).await, 400);
+
+    // A rejected resolution leaves the dispute open
+    let dispute_id = open_dispute(&fixture, group_id, dinner_id).await;
+
+    let reply = resolve_dispute(&fixture, group_id, dispute_id, DisputeResolution::Amend(0)).await;
+    assert_eq!(reply, Events::Error("Amount must be greater than zero".to_owned()));
+
+    let reply = resolve_dispute(&fixture, group_id, dispute_id, DisputeResolution::Amend(u128::MAX)).await;
+    assert_eq!(reply, Events::Error("Amount is too large".to_owned()));
+
+    let reply = resolve_dispute(&fixture, group_id, dispute_id, DisputeResolution::Amend(100)).await;
+    assert!(matches!(reply, Events::DisputeResolved(_)));
+    assert_eq!(spent(&fixture, group_id).await, 100);
+
+    let Events::ExpenseAdded((_, snack_id)) = add_expense(&fixture, ALICE, group_id, expense_dto(200, &[])).await else {
+        panic!("Expense not added");
+    };
+    assert_eq!(spent(&fixture, group_id).await, 300);
+
+    // A larger amount over the approval threshold has to be approved again
+    let reply = admin
+        .set_approval_policy(group_id, Some(500), 1)
+        .send_recv(fixture.program_id)
+        .await
+        .unwrap();
+    assert_eq!(reply, Events::ApprovalPolicySet(group_id));
+
+    let reply = resolve(&fixture, group_id, snack_id, DisputeResolution::Amend(900)).await;
+    assert!(matches!(reply, Events::DisputeResolved(_)));
+    assert_eq!(spent(&fixture, group_id).await, 100);
+
+    let expenses = admin.query_expenses(group_id).recv(fixture.program_id).await.unwrap().unwrap();
+    let snack = expenses.iter().find(|e| e.id == snack_id).unwrap();
+    assert_eq!(snack.status, ExpenseStatus::Pending);
+    assert!(snack.approvals.is_empty());
+}
+
+#[tokio::test]
+async fn add_expenses_batch_is_atomic() {
+    let fixture = Fixture::new().await;
+    let group_id = group_with_members(&fixture).await;
+    let mut service = fixture.service(ALICE);
+
+    let reply = service
+        .add_expenses_batch(group_id, vec![expense_dto(100, &[]), expense_dto(50, &[ALICE, CAROL])])
+        .send_recv(fixture.program_id)
+        .await
+        .unwrap();
+    assert_eq!(reply, Events::Error("Participants must be members of the group".to_owned()));
+
+    let expenses = service.query_expenses(group_id).recv(fixture.program_id).await.unwrap().unwrap();
+    assert!(expenses.is_empty());
+
+    let reply = service
+        .add_expenses_batch(group_id, vec![expense_dto(90, &[]), expense_dto(50, &[ALICE, BOB])])
+        .send_recv(fixture.program_id)
+        .await
+        .unwrap();
+    assert_eq!(reply, Events::ExpensesBatchAdded((group_id, 2)));
+
+    let expenses = service.query_expenses(group_id).recv(fixture.program_id).await.unwrap().unwrap();
+    assert_eq!(expenses.len(), 2);
+    assert_eq!(balance_of(&fixture, group_id, ALICE).await, 85);
+}
+
+#[tokio::test]
+async fn batch_applies_all_actions_or_none() {
+    let fixture = Fixture::new().await;
+    let group_id = group_with_members(&fixture).await;
+    let mut service = fixture.service(BOB);
+
+    let reply = service
+        .batch(group_id, vec![
+            GroupAction::AddExpense(expense_dto(60, &[ALICE, BOB])),
+            GroupAction::ConfirmPayment(1000),
+        ])
+        .send_recv(fixture.program_id)
+        .await
+        .unwrap();
+    assert_eq!(reply, Events::Error("Payment not found".to_owned()));
+
+    let expenses = service.query_expenses(group_id).recv(fixture.program_id).await.unwrap().unwrap();
+    assert!(expenses.is_empty());
+
+    let reply = service
+        .batch(group_id, vec![
+            GroupAction::AddExpense(expense_dto(60, &[ALICE, BOB])),
+            GroupAction::AddPayment { amount: 10, to: ALICE.into() },
+        ])
+        .send_recv(fixture.program_id)
+        .await
+        .unwrap();
+    let Events::BatchApplied((_, ids)) = reply else {
+        panic!("Unexpected reply: {reply:?}");
+    };
+
+    let expenses = service.query_expenses(group_id).recv(fixture.program_id).await.unwrap().unwrap();
+    let payments = service.query_payments(group_id).recv(fixture.program_id).await.unwrap().unwrap();
+    assert_eq!(payments.len(), 1);
+    assert_eq!(ids, vec![expenses[0].id, payments[0].id]);
+    assert_eq!(balance_of(&fixture, group_id, BOB).await, 30);
+
+    let reply = service
+        .batch(group_id + 100, vec![GroupAction::ConfirmPayment(payments[0].id)])
+        .send_recv(fixture.program_id)
+        .await
+        .unwrap();
+    assert_eq!(reply, Events::Error("Group not found".to_owned()));
+}
+
+#[tokio::test]
+async fn add_payment_settles_the_balances() {
+    let fixture = Fixture::new().await;
+    let group_id = group_with_members(&fixture).await;
+
+    add_expense(&fixture, ALICE, group_id, expense_dto(200, &[ALICE, BOB])).await;
+
+    let plan = fixture.service(BOB)
+        .query_settlement_plan(group_id)
+        .recv(fixture.program_id)
+        .await
+        .unwrap()
+        .unwrap();
+
+    assert_eq!(plan.len(), 1);
+    assert_eq!((plan[0].from, plan[0].to, plan[0].amount), (BOB.into(), ALICE.into(), 100));
+
+    let reply = fixture.service(BOB)
+        .add_payment(group_id, 100, ALICE.into())
+        .send_recv(fixture.program_id)
+        .await
+        .unwrap();
+    assert_eq!(reply, Events::PaymentAdded((group_id, 100)));
+
+    let payments = fixture.service(BOB)
+        .query_payments(group_id)
+        .recv(fixture.program_id)
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(payments.len(), 1);
+    assert_eq!(payments[0].status, PaymentStatus::Unconfirmed);
+
+    // Unconfirmed payments do not change the balances
+    assert_eq!(balance_of(&fixture, group_id, BOB).await, -100);
+
+    let payment_id = payments[0].id;
+
+    let reply = fixture.service(BOB)
+        .confirm_payment(group_id, payment_id)
+        .send_recv(fixture.program_id)
+        .await
+        .unwrap();
+    assert_eq!(reply, Events::Error("Only the recipient can confirm the payment".to_owned()));
+
+    let reply = fixture.service(ALICE)
+        .confirm_payment(group_id, payment_id)
+        .send_recv(fixture.program_id)
+        .await
+        .unwrap();
+    assert_eq!(reply, Events::PaymentConfirmed((group_id, payment_id)));
+
+    assert_eq!(balance_of(&fixture, group_id, ALICE).await, 0);
+    assert_eq!(balance_of(&fixture, group_id, BOB).await, 0);
+
+    let plan = fixture.service(BOB)
+        .query_settlement_plan(group_id)
+        .recv(fixture.program_id)
+        .await
+        .unwrap()
+        .unwrap();
+    assert!(plan.is_empty());
+}
+
+#[tokio::test]
+async fn add_payment_fails_for_missing_group() {
+    let fixture = Fixture::new().await;
+
+    let reply = fixture.service(BOB)
+        .add_payment(7, 100, ALICE.into())
+        .send_recv(fixture.program_id)
+        .await
+        .unwrap();
+
+    assert_eq!(reply, Events::Error("Group not found".to_owned()));
+}
+
+#[tokio::test]
+async fn payments_are_between_members() {
+    let fixture = Fixture::new().await;
+    let group_id = group_with_members(&fixture).await;
+
+    let reply = fixture.service(CAROL)
+        .add_payment(group_id, 100, ALICE.into())
+        .send_recv(fixture.program_id)
+        .await
+        .unwrap();
+    assert_eq!(reply, Events::Error("User is not a member of the group".to_owned()));
+
+    let reply = fixture.service(BOB)
+        .add_payment(group_id, 100, CAROL.into())
+        .send_recv(fixture.program_id)
+        .await
+        .unwrap();
+    assert_eq!(reply, Events::Error("Recipient is not a member of the group".to_owned()));
+
+    let reply = fixture.service(CAROL)
+        .batch(group_id, vec![GroupAction::AddPayment { amount: 100, to: ALICE.into() }])
+        .send_recv(fixture.program_id)
+        .await
+        .unwrap();
+    assert_eq!(reply, Events::Error("User is not a member of the group".to_owned()));
+
+    let payments = fixture.service(BOB).query_payments(group_id).recv(fixture.program_id).await.unwrap().unwrap();
+    assert!(payments.is_empty());
+}
+
+#[tokio::test]
+async fn member_statement_explains_the_balance() {
+    let fixture = Fixture::new().await;
+    let group_id = group_with_members(&fixture).await;
+
+    let Events::ExpenseAdded((_, dinner_id)) = add_expense(&fixture, ALICE, group_id, expense_dto(200, &[ALICE, BOB])).await else {
+        panic!("Expense not added");
+    };
+    let Events::ExpenseAdded((_, taxi_id)) = add_expense(&fixture, BOB, group_id, expense_dto(60, &[ALICE, BOB])).await else {
+        panic!("Expense not added");
+    };
+
+    fixture.service(BOB)
+        .add_payment(group_id, 70, ALICE.into())
+        .send_recv(fixture.program_id)
+        .await
+        .unwrap();
+    let payments = fixture.service(BOB)
+        .query_payments(group_id)
+        .recv(fixture.program_id)
+        .await
+        .unwrap()
+        .unwrap();
+    let payment_id = payments[0].id;
+    fixture.service(ALICE)
+        .confirm_payment(group_id, payment_id)
+        .send_recv(fixture.program_id)
+        .await
+        .unwrap();
+
+    let statement = fixture.service(ALICE)
+        .query_member_statement(group_id, ALICE.into())
+        .recv(fixture.program_id)
+        .await
+        .unwrap()
+        .unwrap();
+
+    let lines: Vec<_> = statement.lines.iter().map(|l| (l.entry.clone(), l.amount, l.balance)).collect();
+    assert_eq!(
+        lines,
+        vec![
+            (StatementEntry::ExpensePaid(dinner_id), 200, 200),
+            (StatementEntry::ExpenseShare(dinner_id), -100, 100),
+            (StatementEntry::ExpenseShare(taxi_id), -30, 70),
+            (StatementEntry::PaymentReceived(payment_id), -70, 0),
+        ]
+    );
+    assert_eq!(statement.balance, balance_of(&fixture, group_id, ALICE).await);
+
+    let statement = fixture.service(BOB)
+        .query_member_statement(group_id, BOB.into())
+        .recv(fixture.program_id)
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(statement.lines.last().map(|l| l.entry.clone()), Some(StatementEntry::PaymentSent(payment_id)));
+    assert_eq!(statement.balance, balance_of(&fixture, group_id, BOB).await);
+
+    // Accounts that are not members of the group have no statement
+    let statement = fixture.service(CAROL)
+        .query_member_statement(group_id, CAROL.into())
+        .recv(fixture.program_id)
+        .await
+        .unwrap();
+    assert!(statement.is_none());
+}
+
+async fn expense_count(fixture: &Fixture, group_id: u32) -> usize {
+    fixture.service(ALICE)
+        .query_expenses(group_id)
+        .recv(fixture.program_id)
+        .await
+        .unwrap()
+        .unwrap()
+        .len()
+}
+
+fn run_blocks(fixture: &Fixture, blocks: u32) {
+    for _ in 0..blocks {
+        fixture.remoting().run_next_block();
+    }
+}
+
+const PERIOD: u32 = 5;
+// Gas the program gives to every pending occurrence of a recurring expense
+const RECURRING_GAS_PER_OCCURRENCE: u64 = 10_000_000_000;
+
+// Creates as `ALICE` a recurring expense of the group added every `PERIOD`
+// blocks, returns its id
+async fn create_recurring(fixture: &Fixture, group_id: u32, occurrences: u32) -> u32 {
+    let reply = fixture.service(ALICE)
+        .create_recurring_expense(group_id, expense_dto(90, &[]), PERIOD, occurrences)
+        .send_recv(fixture.program_id)
+        .await
+        .unwrap();
+
+    let Events::RecurringExpenseCreated((_, recurring_id)) = reply else {
+        panic!("Unexpected reply: {reply:?}");
+    };
+    recurring_id
+}
+
+#[tokio::test]
+async fn recurring_expense_adds_occurrences() {
+    let fixture = Fixture::new().await;
+    let group_id = group_with_members(&fixture).await;
+    create_recurring(&fixture, group_id, 2).await;
+
+    run_blocks(&fixture, PERIOD);
+    assert_eq!(expense_count(&fixture, group_id).await, 1);
+
+    let recurring = fixture.service(ALICE)
+        .query_recurring_expenses(group_id)
+        .recv(fixture.program_id)
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(recurring[0].remaining, 1);
+
+    // The last occurrence removes the recurring expense
+    run_blocks(&fixture, PERIOD);
+    assert_eq!(expense_count(&fixture, group_id).await, 2);
+    assert_eq!(balance_of(&fixture, group_id, ALICE).await, 120);
+
+    let recurring = fixture.service(ALICE)
+        .query_recurring_expenses(group_id)
+        .recv(fixture.program_id)
+        .await
+        .unwrap()
+        .unwrap();
+    assert!(recurring.is_empty());
+}
+
+#[tokio::test]
+async fn recurring_expense_pause_and_cancel() {
+    let fixture = Fixture::new().await;
+    let group_id = group_with_members(&fixture).await;
+    let recurring_id = create_recurring(&fixture, group_id, 3).await;
+
+    let reply = fixture.service(BOB)
+        .pause_recurring_expense(group_id, recurring_id)
+        .send_recv(fixture.program_id)
+        .await
+        .unwrap();
+    assert_eq!(reply, Events::Error("Only the creator can pause the recurring expense".to_owned()));
+
+    let reply = fixture.service(ALICE)
+        .pause_recurring_expense(group_id, recurring_id)
+        .send_recv(fixture.program_id)
+        .await
+        .unwrap();
+    assert_eq!(reply, Events::RecurringExpensePaused((group_id, recurring_id)));
+
+    run_blocks(&fixture, PERIOD * 2);
+    assert_eq!(expense_count(&fixture, group_id).await, 0);
+
+    let reply = fixture.service(ALICE)
+        .resume_recurring_expense(group_id, recurring_id)
+        .send_recv(fixture.program_id)
+        .await
+        .unwrap();
+    assert_eq!(reply, Events::RecurringExpenseResumed((group_id, recurring_id)));
+
+    run_blocks(&fixture, PERIOD);
+    assert_eq!(expense_count(&fixture, group_id).await, 1);
+
+    let reply = fixture.service(ALICE)
+        .cancel_recurring_expense(group_id, recurring_id)
+        .send_recv(fixture.program_id)
+        .await
+        .unwrap();
+    assert_eq!(reply, Events::RecurringExpenseCancelled((group_id, recurring_id)));
+
+    run_blocks(&fixture, PERIOD * 2);
+    assert_eq!(expense_count(&fixture, group_id).await, 1);
+}
+
+#[tokio::test]
+async fn recurring_expense_is_paused_with_the_program() {
+    let fixture = Fixture::new().await;
+    let group_id = group_with_members(&fixture).await;
+    let recurring_id = create_recurring(&fixture, group_id, 2).await;
+
+    let reply = fixture.service(ADMIN).pause().send_recv(fixture.program_id).await.unwrap();
+    assert_eq!(reply, Events::ProgramPaused);
+
+    run_blocks(&fixture, PERIOD);
+
+    let reply = fixture.service(ADMIN).unpause().send_recv(fixture.program_id).await.unwrap();
+    assert_eq!(reply, Events::ProgramUnpaused);
+    assert_eq!(expense_count(&fixture, group_id).await, 0);
+
+    let recurring = fixture.service(ALICE)
+        .query_recurring_expenses(group_id)
+        .recv(fixture.program_id)
+        .await
+        .unwrap()
+        .unwrap();
+    assert!(recurring[0].paused);
+    assert_eq!(recurring[0].remaining, 2);
+
+    let reply = fixture.service(ALICE)
+        .resume_recurring_expense(group_id, recurring_id)
+        .send_recv(fixture.program_id)
+        .await
+        .unwrap();
+    assert_eq!(reply, Events::RecurringExpenseResumed((group_id, recurring_id)));
+
+    run_blocks(&fixture, PERIOD);
+    assert_eq!(expense_count(&fixture, group_id).await, 1);
+}
+
+#[tokio::test]
+async fn recurring_expense_needs_gas_for_its_occurrences() {
+    let fixture = Fixture::new().await;
+    let group_id = group_with_members(&fixture).await;
+
+    let reply = fixture.service(ALICE)
+        .create_recurring_expense(group_id, expense_dto(90, &[]), PERIOD, 3)
+        .with_gas_limit(RECURRING_GAS_PER_OCCURRENCE * 2)
+        .send_recv(fixture.program_id)
+        .await
+        .unwrap();
+    assert_eq!(reply, Events::Error("Not enough gas to schedule the recurring expense".to_owned()));
+}
+
+// One VARA, values sent from the program must not be below the existential
+// deposit
+const UNIT: u128 = 1_000_000_000_000;
+
+#[tokio::test]
+async fn treasury_payments_need_a_majority_of_admins() {
+    let fixture = Fixture::new().await;
+    let group_id = group_with_members(&fixture).await;
+    let mut admin = fixture.service(ADMIN);
+
+    let reply = admin.add_admin(group_id, ALICE.into()).send_recv(fixture.program_id).await.unwrap();
+    assert_eq!(reply, Events::AdminAdded((ALICE.into(), group_id)));
+
+    let reply = fixture.service(BOB)
+        .deposit(group_id)
+        .with_value(10 * UNIT)
+        .send_recv(fixture.program_id)
+        .await
+        .unwrap();
+    assert_eq!(reply, Events::Deposited((group_id, 10 * UNIT)));
+
+    // Two admins without a quorum need both approvals
+    let reply = admin
+        .pay_from_pool(group_id, CAROL.into(), 3 * UNIT, "Cleaning".to_owned())
+        .send_recv(fixture.program_id)
+        .await
+        .unwrap();
+    let Events::WithdrawalProposed((_, proposal_id)) = reply else {
+        panic!("Unexpected reply: {reply:?}");
+    };
+
+    let reply = admin.execute_withdrawal(proposal_id).send_recv(fixture.program_id).await.unwrap();
+    assert_eq!(reply, Events::Error("Withdrawal does not have enough approvals".to_owned()));
+
+    let reply = fixture.service(ALICE)
+        .approve_withdrawal(proposal_id)
+        .send_recv(fixture.program_id)
+        .await
+        .unwrap();
+    assert_eq!(reply, Events::WithdrawalApproved((group_id, proposal_id)));
+
+    let reply = admin.execute_withdrawal(proposal_id).send_recv(fixture.program_id).await.unwrap();
+    assert_eq!(reply, Events::WithdrawalExecuted((group_id, proposal_id)));
+
+    let group = admin.query_group(group_id).recv(fixture.program_id).await.unwrap();
+    assert_eq!(group.pool_payments.len(), 1);
+    assert_eq!(group.pool_payments[0].description, "Cleaning");
+    assert_eq!(group.pool_payments[0].amount, 3 * UNIT);
+
+    // With a quorum of one any admin pays alone
+    let reply = admin.set_withdrawal_quorum(group_id, 1).send_recv(fixture.program_id).await.unwrap();
+    assert_eq!(reply, Events::WithdrawalQuorumSet((group_id, 1)));
+
+    let reply = admin
+        .pay_from_pool(group_id, CAROL.into(), 2 * UNIT, "Repairs".to_owned())
+        .send_recv(fixture.program_id)
+        .await
+        .unwrap();
+    assert_eq!(reply, Events::PoolPaid((group_id, 2 * UNIT)));
+}
+
+#[tokio::test]
+async fn withdrawal_quorum_errors() {
+    let fixture = Fixture::new().await;
+    let group_id = group_with_members(&fixture).await;
+    let mut admin = fixture.service(ADMIN);
+    let mut alice = fixture.service(ALICE);
+
+    admin.add_admin(group_id, ALICE.into()).send_recv(fixture.program_id).await.unwrap();
+    fixture.service(BOB).deposit(group_id).with_value(10 * UNIT).send_recv(fixture.program_id).await.unwrap();
+
+    for quorum in [0, 3] {
+        let reply = admin.set_withdrawal_quorum(group_id, quorum).send_recv(fixture.program_id).await.unwrap();
+        assert_eq!(reply, Events::Error("Quorum must be between one and the number of admins".to_owned()));
+    }
+
+    let reply = fixture.service(BOB)
+        .set_withdrawal_quorum(group_id, 1)
+        .send_recv(fixture.program_id)
+        .await
+        .unwrap();
+    assert_eq!(reply, Events::Error("Only an admin can set the withdrawal quorum".to_owned()));
+
+    let reply = admin.propose_withdrawal(group_id, CAROL.into(), 0, 10).send_recv(fixture.program_id).await.unwrap();
+    assert_eq!(reply, Events::Error("Amount and expiry must be greater than zero".to_owned()));
+
+    let reply = admin
+        .propose_withdrawal(group_id, CAROL.into(), 11 * UNIT, 10)
+        .send_recv(fixture.program_id)
+        .await
+        .unwrap();
+    assert_eq!(reply, Events::Error("Not enough funds in the group treasury".to_owned()));
+
+    let reply = fixture.service(BOB)
+        .propose_withdrawal(group_id, BOB.into(), UNIT, 10)
+        .send_recv(fixture.program_id)
+        .await
+        .unwrap();
+    assert_eq!(reply, Events::Error("Only an admin can propose a withdrawal".to_owned()));
+
+    let reply = admin.approve_withdrawal(99).send_recv(fixture.program_id).await.unwrap();
+    assert_eq!(reply, Events::Error("Withdrawal proposal not found".to_owned()));
+
+    let reply = admin.propose_withdrawal(group_id, CAROL.into(), UNIT, 10).send_recv(fixture.program_id).await.unwrap();
+    let Events::WithdrawalProposed((_, proposal_id)) = reply else {
+        panic!("Unexpected reply: {reply:?}");
+    };
+
+    let reply = admin.approve_withdrawal(proposal_id).send_recv(fixture.program_id).await.unwrap();
+    assert_eq!(reply, Events::Error("Withdrawal already approved by user".to_owned()));
+
+    let reply = fixture.service(BOB).approve_withdrawal(proposal_id).send_recv(fixture.program_id).await.unwrap();
+    assert_eq!(reply, Events::Error("Only an admin can approve a withdrawal".to_owned()));
+
+    alice.approve_withdrawal(proposal_id).send_recv(fixture.program_id).await.unwrap();
+    let reply = admin.execute_withdrawal(proposal_id).send_recv(fixture.program_id).await.unwrap();
+    assert_eq!(reply, Events::WithdrawalExecuted((group_id, proposal_id)));
+
+    let reply = admin.execute_withdrawal(proposal_id).send_recv(fixture.program_id).await.unwrap();
+    assert_eq!(reply, Events::Error("Withdrawal already executed".to_owned()));
+
+    // An expired proposal can be neither approved nor executed
+    let reply = admin.propose_withdrawal(group_id, CAROL.into(), UNIT, 1).send_recv(fixture.program_id).await.unwrap();
+    let Events::WithdrawalProposed((_, proposal_id)) = reply else {
+        panic!("Unexpected reply: {reply:?}");
+    };
+
+    run_blocks(&fixture, 2);
+
+    let reply = alice.approve_withdrawal(proposal_id).send_recv(fixture.program_id).await.unwrap();
+    assert_eq!(reply, Events::Error("Withdrawal proposal expired".to_owned()));
+
+    let reply = admin.execute_withdrawal(proposal_id).send_recv(fixture.program_id).await.unwrap();
+    assert_eq!(reply, Events::Error("Withdrawal proposal expired".to_owned()));
+}
+
+// Amount of the treasury of the group held for `actor`
+async fn pool_of(fixture: &Fixture, group_id: u32, actor: u64) -> u128 {
+    fixture.service(actor)
+        .query_pool(group_id)
+        .recv(fixture.program_id)
+        .await
+        .unwrap()
+        .expect("Group not found")
+        .iter()
+        .find(|b| b.actor_id == actor.into())
+        .map(|b| b.amount)
+        .unwrap_or_default()
+}
+
+#[tokio::test]
+async fn rejected_deposits_and_pool_payments_keep_the_funds() {
+    let fixture = Fixture::new().await;
+    let group_id = group_with_members(&fixture).await;
+    let mut admin = fixture.service(ADMIN);
+
+    fixture.service(BOB).deposit(group_id).with_value(4 * UNIT).send_recv(fixture.program_id).await.unwrap();
+    let program_balance = fixture.program_balance();
+
+    let reply = fixture.service(BOB).deposit(group_id).send_recv(fixture.program_id).await.unwrap();
+    assert_eq!(reply, Events::Error("No value attached to deposit".to_owned()));
+
+    // The value of a rejected deposit is sent back with the reply
+    let reply = fixture.service(CAROL)
+        .deposit(group_id)
+        .with_value(2 * UNIT)
+        .send_recv(fixture.program_id)
+        .await
+        .unwrap();
+    assert_eq!(reply, Events::Error("User is not a member of the group".to_owned()));
+
+    let reply = fixture.service(BOB).deposit(7).with_value(2 * UNIT).send_recv(fixture.program_id).await.unwrap();
+    assert_eq!(reply, Events::Error("Group not found".to_owned()));
+
+    assert_eq!(fixture.program_balance(), program_balance);
+
+    let reply = admin
+        .pay_from_pool(group_id, CAROL.into(), 0, "Cleaning".to_owned())
+        .send_recv(fixture.program_id)
+        .await
+        .unwrap();
+    assert_eq!(reply, Events::Error("Amount must be greater than zero".to_owned()));
+
+    let reply = admin
+        .pay_from_pool(group_id, CAROL.into(), 5 * UNIT, "Cleaning".to_owned())
+        .send_recv(fixture.program_id)
+        .await
+        .unwrap();
+    assert_eq!(reply, Events::Error("Not enough funds in the group treasury".to_owned()));
+
+    let reply = fixture.service(BOB)
+        .pay_from_pool(group_id, BOB.into(), UNIT, "Cleaning".to_owned())
+        .send_recv(fixture.program_id)
+        .await
+        .unwrap();
+    assert_eq!(reply, Events::Error("Only an admin can pay from the group treasury".to_owned()));
+
+    assert_eq!(pool_of(&fixture, group_id, BOB).await, 4 * UNIT);
+    assert_eq!(fixture.program_balance(), program_balance);
+}
+
+#[tokio::test]
+async fn pause_and_unpause_errors() {
+    let fixture = Fixture::new().await;
+    let group_id = group_with_members(&fixture).await;
+    let mut admin = fixture.service(ADMIN);
+
+    let reply = fixture.service(ALICE).pause().send_recv(fixture.program_id).await.unwrap();
+    assert_eq!(reply, Events::Error("Only the program admin can pause the program".to_owned()));
+
+    let reply = admin.unpause().send_recv(fixture.program_id).await.unwrap();
+    assert_eq!(reply, Events::Error("Program is not paused".to_owned()));
+
+    let reply = admin.pause().send_recv(fixture.program_id).await.unwrap();
+    assert_eq!(reply, Events::ProgramPaused);
+
+    let reply = admin.pause().send_recv(fixture.program_id).await.unwrap();
+    assert_eq!(reply, Events::Error("Program is paused".to_owned()));
+
+    // Commands that change the state are rejected, queries still work
+    let reply = add_expense(&fixture, ALICE, group_id, expense_dto(300, &[])).await;
+    assert_eq!(reply, Events::Error("Program is paused".to_owned()));
+
+    let reply = fixture.service(CAROL).join_group(group_id).send_recv(fixture.program_id).await.unwrap();
+    assert_eq!(reply, Events::Error("Program is paused".to_owned()));
+
+    assert_eq!(expense_count(&fixture, group_id).await, 0);
+
+    let reply = fixture.service(ALICE).unpause().send_recv(fixture.program_id).await.unwrap();
+    assert_eq!(reply, Events::Error("Only the program admin can unpause the program".to_owned()));
+
+    let reply = admin.unpause().send_recv(fixture.program_id).await.unwrap();
+    assert_eq!(reply, Events::ProgramUnpaused);
+
+    let reply = add_expense(&fixture, ALICE, group_id, expense_dto(300, &[])).await;
+    assert!(matches!(reply, Events::ExpenseAdded(_)));
+}
+
+#[tokio::test]
+async fn close_group_refunds_the_treasury_and_archives_the_group() {
+    let fixture = Fixture::new().await;
+    let group_id = group_with_members(&fixture).await;
+    let mut admin = fixture.service(ADMIN);
+
+    fixture.service(BOB).deposit(group_id).with_value(3 * UNIT).send_recv(fixture.program_id).await.unwrap();
+    add_expense(&fixture, ALICE, group_id, expense_dto(300, &[])).await;
+
+    let reply = fixture.service(ALICE).close_group(group_id, false).send_recv(fixture.program_id).await.unwrap();
+    assert_eq!(reply, Events::Error("Only an admin can close the group".to_owned()));
+
+    let reply = admin.close_group(group_id, false).send_recv(fixture.program_id).await.unwrap();
+    assert_eq!(reply, Events::Error("Group balances are not settled".to_owned()));
+
+    let program_balance = fixture.program_balance();
+
+    let reply = admin.close_group(group_id, true).send_recv(fixture.program_id).await.unwrap();
+    assert_eq!(reply, Events::GroupClosed(group_id));
+
+    // The treasury is sent back to the members
+    assert_eq!(fixture.program_balance(), program_balance - 3 * UNIT);
+
+    let archived = admin.query_archived_group(group_id).recv(fixture.program_id).await.unwrap().unwrap();
+    assert_eq!(archived.expense_count, 1);
+    assert_eq!(archived.members.len(), 3);
+    assert!(!archived.settlement.is_empty());
+    assert_eq!(admin.query_pool(group_id).recv(fixture.program_id).await.unwrap(), None);
+
+    // A closed group rejects every mutation
+    let reply = add_expense(&fixture, ALICE, group_id, expense_dto(300, &[])).await;
+    assert_eq!(reply, Events::Error("Group is closed".to_owned()));
+
+    let reply = fixture.service(CAROL).join_group(group_id).send_recv(fixture.program_id).await.unwrap();
+    assert_eq!(reply, Events::Error("Group is closed".to_owned()));
+
+    let reply = fixture.service(BOB).deposit(group_id).with_value(UNIT).send_recv(fixture.program_id).await.unwrap();
+    assert_eq!(reply, Events::Error("Group is closed".to_owned()));
+    assert_eq!(fixture.program_balance(), program_balance - 3 * UNIT);
+
+    let reply = admin.close_group(group_id, true).send_recv(fixture.program_id).await.unwrap();
+    assert_eq!(reply, Events::Error("Group is closed".to_owned()));
+}
+
+// Treasury payments are taken pro rata, so the shares left can be below the
+// existential deposit and must not stop the group from closing
+#[tokio::test]
+async fn close_group_keeps_the_dust_of_the_treasury() {
+    let fixture = Fixture::new().await;
+    let group_id = group_with_members(&fixture).await;
+    let mut admin = fixture.service(ADMIN);
+
+    for (member, value) in [(ALICE, UNIT), (BOB, 10 * UNIT)] {
+        fixture.service(member).deposit(group_id).with_value(value).send_recv(fixture.program_id).await.unwrap();
+    }
+
+    let reply = admin
+        .pay_from_pool(group_id, CAROL.into(), 10 * UNIT, "Tickets".to_owned())
+        .send_recv(fixture.program_id)
+        .await
+        .unwrap();
+    assert_eq!(reply, Events::PoolPaid((group_id, 10 * UNIT)));
+
+    for member in [ALICE, BOB] {
+        assert!(pool_of(&fixture, group_id, member).await < UNIT);
+    }
+    let program_balance = fixture.program_balance();
+
+    let reply = admin.close_group(group_id, false).send_recv(fixture.program_id).await.unwrap();
+    assert_eq!(reply, Events::GroupClosed(group_id));
+    assert_eq!(fixture.program_balance(), program_balance);
+}
+
+#[tokio::test]
+async fn budget_errors() {
+    let fixture = Fixture::new().await;
+    let group_id = group_with_members(&fixture).await;
+    let mut admin = fixture.service(ADMIN);
+
+    for (limit, period) in [(0, 100), (1_000, 0)] {
+        let reply = admin
+            .set_budget(group_id, None, "VARA".to_owned(), limit, period, vec![])
+            .send_recv(fixture.program_id)
+            .await
+            .unwrap();
+        assert_eq!(reply, Events::Error("Limit and period must be greater than zero".to_owned()));
+    }
+
+    for thresholds in [vec![0], vec![50, 101]] {
+        let reply = admin
+            .set_budget(group_id, None, "VARA".to_owned(), 1_000, 100, thresholds)
+            .send_recv(fixture.program_id)
+            .await
+            .unwrap();
+        assert_eq!(reply, Events::Error("Thresholds must be percentages between 1 and 100".to_owned()));
+    }
+
+    let reply = admin
+        .set_budget(group_id, None, "V".repeat(9), 1_000, 100, vec![])
+        .send_recv(fixture.program_id)
+        .await
+        .unwrap();
+    assert_eq!(reply, Events::LimitExceeded(LimitError::CurrencyTooLong));
+
+    let reply = fixture.service(ALICE)
+        .set_budget(group_id, None, "VARA".to_owned(), 1_000, 100, vec![])
+        .send_recv(fixture.program_id)
+        .await
+        .unwrap();
+    assert_eq!(reply, Events::Error("Only an admin can set the budget".to_owned()));
+
+    let reply = admin
+        .set_budget(7, None, "VARA".to_owned(), 1_000, 100, vec![])
+        .send_recv(fixture.program_id)
+        .await
+        .unwrap();
+    assert_eq!(reply, Events::Error("Group not found".to_owned()));
+
+    let reply = admin.remove_budget(group_id, None, "VARA".to_owned()).send_recv(fixture.program_id).await.unwrap();
+    assert_eq!(reply, Events::Error("Budget not found".to_owned()));
+
+    let budgets = admin.query_budget_status(group_id).recv(fixture.program_id).await.unwrap().unwrap();
+    assert!(budgets.is_empty());
+}
+
+#[tokio::test]
+async fn approval_errors() {
+    let fixture = Fixture::new().await;
+    let group_id = group_with_members(&fixture).await;
+    let mut admin = fixture.service(ADMIN);
+    let mut bob = fixture.service(BOB);
+
+    let reply = admin.set_approval_policy(group_id, Some(100), 0).send_recv(fixture.program_id).await.unwrap();
+    assert_eq!(reply, Events::Error("Required approvals must be greater than zero".to_owned()));
+
+    let reply = bob.set_approval_policy(group_id, Some(100), 1).send_recv(fixture.program_id).await.unwrap();
+    assert_eq!(reply, Events::Error("Only an admin can set the approval policy".to_owned()));
+
+    admin.set_approval_policy(group_id, Some(100), 2).send_recv(fixture.program_id).await.unwrap();
+
+    let Events::ExpenseAdded((_, expense_id)) = add_expense(&fixture, ALICE, group_id, expense_dto(500, &[])).await else {
+        panic!("Expense not added");
+    };
+
+    let reply = fixture.service(ALICE).approve_expense(group_id, expense_id).send_recv(fixture.program_id).await.unwrap();
+    assert_eq!(reply, Events::Error("The payer cannot approve the expense".to_owned()));
+
+    let reply = fixture.service(CAROL).approve_expense(group_id, expense_id).send_recv(fixture.program_id).await.unwrap();
+    assert_eq!(reply, Events::Error("User is not a member of the group".to_owned()));
+
+    let reply = bob.approve_expense(group_id, 99).send_recv(fixture.program_id).await.unwrap();
+    assert_eq!(reply, Events::Error("Expense not found".to_owned()));
+
+    let reply = bob.approve_expense(group_id, expense_id).send_recv(fixture.program_id).await.unwrap();
+    assert_eq!(reply, Events::ExpenseApprovalAdded((group_id, expense_id)));
+
+    let reply = bob.approve_expense(group_id, expense_id).send_recv(fixture.program_id).await.unwrap();
+    assert_eq!(reply, Events::Error("Expense already approved by user".to_owned()));
+
+    let reply = admin.approve_expense(group_id, expense_id).send_recv(fixture.program_id).await.unwrap();
+    assert_eq!(reply, Events::ExpenseApproved((group_id, expense_id)));
+
+    let reply = bob.approve_expense(group_id, expense_id).send_recv(fixture.program_id).await.unwrap();
+    assert_eq!(reply, Events::Error("Expense is not pending".to_owned()));
+
+    let reply = bob.reject_expense(group_id, expense_id).send_recv(fixture.program_id).await.unwrap();
+    assert_eq!(reply, Events::Error("Expense is not pending".to_owned()));
+
+    let Events::ExpenseAdded((_, expense_id)) = add_expense(&fixture, ALICE, group_id, expense_dto(500, &[])).await else {
+        panic!("Expense not added");
+    };
+
+    let reply = fixture.service(ALICE).reject_expense(group_id, expense_id).send_recv(fixture.program_id).await.unwrap();
+    assert_eq!(reply, Events::Error("The payer cannot reject the expense".to_owned()));
+
+    let reply = bob.reject_expense(group_id, expense_id).send_recv(fixture.program_id).await.unwrap();
+    assert_eq!(reply, Events::ExpenseRejected((group_id, expense_id)));
+}
+
+#[tokio::test]
+async fn dispute_errors() {
+    let fixture = Fixture::new().await;
+    let group_id = group_with_members(&fixture).await;
+    let mut admin = fixture.service(ADMIN);
+    let mut bob = fixture.service(BOB);
+
+    let Events::ExpenseAdded((_, expense_id)) = add_expense(&fixture, ALICE, group_id, expense_dto(300, &[ALICE, BOB])).await else {
+        panic!("Expense not added");
+    };
+
+    let reply = admin
+        .open_dispute(group_id, expense_id, "Wrong amount".to_owned())
+        .send_recv(fixture.program_id)
+        .await
+        .unwrap();
+    assert_eq!(reply, Events::Error("User is not affected by the expense".to_owned()));
+
+    let reply = bob.open_dispute(group_id, 99, "Wrong amount".to_owned()).send_recv(fixture.program_id).await.unwrap();
+    assert_eq!(reply, Events::Error("Expense not found".to_owned()));
+
+    let reply = bob
+        .open_dispute(group_id, expense_id, "a".repeat(257))
+        .send_recv(fixture.program_id)
+        .await
+        .unwrap();
+    assert_eq!(reply, Events::LimitExceeded(LimitError::DescriptionTooLong));
+
+    let reply = bob
+        .open_dispute(group_id, expense_id, "Wrong amount".to_owned())
+        .send_recv(fixture.program_id)
+        .await
+        .unwrap();
+    let Events::DisputeOpened((_, dispute_id)) = reply else {
+        panic!("Unexpected reply: {reply:?}");
+    };
+
+    let reply = fixture.service(ALICE)
+        .open_dispute(group_id, expense_id, "Wrong amount".to_owned())
+        .send_recv(fixture.program_id)
+        .await
+        .unwrap();
+    assert_eq!(reply, Events::Error("Expense already disputed".to_owned()));
+
+    let reply = bob
+        .resolve_dispute(group_id, dispute_id, DisputeResolution::Void)
+        .send_recv(fixture.program_id)
+        .await
+        .unwrap();
+    assert_eq!(reply, Events::Error("Only an admin can resolve a dispute".to_owned()));
+
+    let reply = admin
+        .resolve_dispute(group_id, 99, DisputeResolution::Void)
+        .send_recv(fixture.program_id)
+        .await
+        .unwrap();
+    assert_eq!(reply, Events::Error("Dispute not found".to_owned()));
+
+    let reply = admin
+        .resolve_dispute(group_id, dispute_id, DisputeResolution::Void)
+        .send_recv(fixture.program_id)
+        .await
+        .unwrap();
+    assert_eq!(reply, Events::DisputeResolved((group_id, dispute_id)));
+
+    let reply = admin
+        .resolve_dispute(group_id, dispute_id, DisputeResolution::Void)
+        .send_recv(fixture.program_id)
+        .await
+        .unwrap();
+    assert_eq!(reply, Events::Error("Dispute already resolved".to_owned()));
+
+    // A voided expense cannot be disputed again
+    let reply = bob
+        .open_dispute(group_id, expense_id, "Wrong amount".to_owned())
+        .send_recv(fixture.program_id)
+        .await
+        .unwrap();
+    assert_eq!(reply, Events::Error("Expense cannot be disputed".to_owned()));
+}
+
+#[tokio::test]
+async fn resolved_disputes_cannot_be_opened_again() {
+    let fixture = Fixture::new().await;
+    let group_id = group_with_members(&fixture).await;
+
+    let Events::ExpenseAdded((_, expense_id)) = add_expense(&fixture, ALICE, group_id, expense_dto(300, &[])).await else {
+        panic!("Expense not added");
+    };
+
+    let reply = resolve(&fixture, group_id, expense_id, DisputeResolution::Uphold).await;
+    assert!(matches!(reply, Events::DisputeResolved(_)));
+
+    for member in [ALICE, BOB] {
+        let reply = fixture.service(member)
+            .open_dispute(group_id, expense_id, "Still wrong".to_owned())
+            .send_recv(fixture.program_id)
+            .await
+            .unwrap();
+        assert_eq!(reply, Events::Error("Dispute of the expense already resolved".to_owned()));
+    }
+
+    let disputes = fixture.service(ADMIN).query_disputes(group_id).recv(fixture.program_id).await.unwrap().unwrap();
+    assert_eq!(disputes.len(), 1);
+}
+
+fn metadata(name: &str) -> GroupMetadataDto {
+    GroupMetadataDto {
+        name: name.to_owned(),
+        description: "Summer trip".to_owned(),
+        avatar_uri: String::new(),
+        avatar_hash: None,
+        default_split_mode: SplitMode::Equal,
+        default_currency: String::new(),
+    }
+}
+
+#[tokio::test]
+async fn update_group_metadata_checks_the_limits() {
+    let fixture = Fixture::new().await;
+    let group_id = group_with_members(&fixture).await;
+    let mut admin = fixture.service(ADMIN);
+
+    let cases = [
+        (metadata(""), Events::Error("Group name cannot be empty".to_owned())),
+        (metadata(&"a".repeat(65)), Events::LimitExceeded(LimitError::NameTooLong)),
+        (
+            GroupMetadataDto { description: "a".repeat(257), ..metadata("Trip") },
+            Events::LimitExceeded(LimitError::DescriptionTooLong),
+        ),
+        (
+            GroupMetadataDto { avatar_uri: "a".repeat(257), ..metadata("Trip") },
+            Events::LimitExceeded(LimitError::UriTooLong),
+        ),
+        (
+            GroupMetadataDto { default_currency: "a".repeat(9), ..metadata("Trip") },
+            Events::LimitExceeded(LimitError::CurrencyTooLong),
+        ),
+    ];
+
+    for (metadata, error) in cases {
+        let reply = admin.update_group_metadata(group_id, metadata).send_recv(fixture.program_id).await.unwrap();
+        assert_eq!(reply, error);
+    }
+
+    let reply = fixture.service(ALICE)
+        .update_group_metadata(group_id, metadata("Holidays"))
+        .send_recv(fixture.program_id)
+        .await
+        .unwrap();
+    assert_eq!(reply, Events::Error("Only an admin can update the group".to_owned()));
+
+    let reply = admin.update_group_metadata(7, metadata("Holidays")).send_recv(fixture.program_id).await.unwrap();
+    assert_eq!(reply, Events::Error("Group not found".to_owned()));
+
+    add_expense(&fixture, ALICE, group_id, expense_dto(300, &[])).await;
+
+    let reply = admin
+        .update_group_metadata(group_id, GroupMetadataDto { default_currency: "EUR".to_owned(), ..metadata("Trip") })
+        .send_recv(fixture.program_id)
+        .await
+        .unwrap();
+    assert_eq!(reply, Events::Error("Currency of a group with expenses cannot be changed".to_owned()));
+
+    let reply = admin.update_group_metadata(group_id, metadata("Holidays")).send_recv(fixture.program_id).await.unwrap();
+    assert_eq!(reply, Events::GroupUpdated(group_id));
+
+    let group = admin.query_group(group_id).recv(fixture.program_id).await.unwrap();
+    assert_eq!(group.name, "Holidays");
+}
+
+// Config of the default constructor sent by `ADMIN`
+fn default_config() -> Config {
+    Config {
+        admin: ADMIN.into(),
+        allowed_currencies: vec![],
+        limits: Limits {
+            max_name_length: 64,
+            max_description_length: 256,
+            max_currency_length: 8,
+            max_uri_length: 256,
+            max_tags: 8,
+            max_members: 100,
+            max_expenses: 1000,
+            max_payments: 1000,
+            max_disputes: 100,
+            max_budgets: 20,
+            max_recurring_expenses: 20,
+        },
+        fees: FeeSettings { fee_bps: 0, fee_collector: ActorId::zero() },
+    }
+}
+
+// Config of an instance where a group can have one payment, dispute, budget
+// and recurring expense
+fn config_with_small_limits() -> Config {
+    let config = default_config();
+    Config {
+        limits: Limits {
+            max_payments: 1,
+            max_disputes: 1,
+            max_budgets: 1,
+            max_recurring_expenses: 1,
+            ..config.limits
+        },
+        ..config
+    }
+}
+
+#[tokio::test]
+async fn groups_stay_within_the_limits() {
+    let fixture = Fixture::with_config(config_with_small_limits()).await;
+    let group_id = group_with_members(&fixture).await;
+    let mut admin = fixture.service(ADMIN);
+    let mut bob = fixture.service(BOB);
+
+    for (amount, reply) in [
+        (10, Events::PaymentAdded((group_id, 10))),
+        (20, Events::LimitExceeded(LimitError::TooManyPayments)),
+    ] {
+        let result = bob.add_payment(group_id, amount, ALICE.into()).send_recv(fixture.program_id).await.unwrap();
+        assert_eq!(result, reply);
+    }
+
+    let Events::ExpenseAdded((_, lunch_id)) = add_expense(&fixture, ALICE, group_id, expense_dto(300, &[])).await else {
+        panic!("Expense not added");
+    };
+    let Events::ExpenseAdded((_, dinner_id)) = add_expense(&fixture, ALICE, group_id, expense_dto(400, &[])).await else {
+        panic!("Expense not added");
+    };
+
+    open_dispute(&fixture, group_id, lunch_id).await;
+    let reply = bob
+        .open_dispute(group_id, dinner_id, "Wrong amount".to_owned())
+        .send_recv(fixture.program_id)
+        .await
+        .unwrap();
+    assert_eq!(reply, Events::LimitExceeded(LimitError::TooManyDisputes));
+
+    // Setting the budget of the same category again replaces it
+    for limit in [1_000, 2_000] {
+        let reply = admin
+            .set_budget(group_id, Some(ExpenseCategory::Food), "VARA".to_owned(), limit, 100, vec![])
+            .send_recv(fixture.program_id)
+            .await
+            .unwrap();
+        assert_eq!(reply, Events::BudgetSet(group_id));
+    }
+    let reply = admin
+        .set_budget(group_id, Some(ExpenseCategory::Transport), "VARA".to_owned(), 1_000, 100, vec![])
+        .send_recv(fixture.program_id)
+        .await
+        .unwrap();
+    assert_eq!(reply, Events::LimitExceeded(LimitError::TooManyBudgets));
+
+    create_recurring(&fixture, group_id, 2).await;
+    let reply = fixture.service(ALICE)
+        .create_recurring_expense(group_id, expense_dto(90, &[]), PERIOD, 2)
+        .send_recv(fixture.program_id)
+        .await
+        .unwrap();
+    assert_eq!(reply, Events::LimitExceeded(LimitError::TooManyRecurringExpenses));
+}
+
+#[tokio::test]
+async fn deposit_fees_below_the_existential_deposit_stay_in_the_program() {
+    let fees = FeeSettings { fee_bps: 100, fee_collector: CAROL.into() };
+    let fixture = Fixture::with_config(Config { fees, ..default_config() }).await;
+    let group_id = group_with_members(&fixture).await;
+    let mut bob = fixture.service(BOB);
+
+    // A fee of 1% of 10 VARA can not be sent to the collector
+    let program_balance = fixture.program_balance();
+    let reply = bob.deposit(group_id).with_value(10 * UNIT).send_recv(fixture.program_id).await.unwrap();
+    assert_eq!(reply, Events::Deposited((group_id, 10 * UNIT - UNIT / 10)));
+    assert_eq!(fixture.program_balance(), program_balance + 10 * UNIT);
+
+    let program_balance = fixture.program_balance();
+    let reply = bob.deposit(group_id).with_value(200 * UNIT).send_recv(fixture.program_id).await.unwrap();
+    assert_eq!(reply, Events::Deposited((group_id, 198 * UNIT)));
+    assert_eq!(fixture.program_balance(), program_balance + 198 * UNIT);
+}
+
+#[tokio::test]
+async fn fees_need_a_collector() {
+    let fixture = Fixture::new().await;
+    let fees = FeeSettings { fee_bps: 100, fee_collector: ActorId::zero() };
+
+    let result = AppFactory::new(fixture.remoting().clone())
+        .new_with_config(Config { fees, ..default_config() })
+        .send_recv(fixture.code_id, b"no_collector")
+        .await;
+    assert!(result.is_err());
+}
+
+#[tokio::test]
+async fn queries_of_missing_group_return_none() {
+    let fixture = Fixture::new().await;
+    let service = fixture.service(ALICE);
+    let program_id = fixture.program_id;
+
+    assert_eq!(service.query_group_members(7).recv(program_id).await.unwrap(), None);
+    assert_eq!(service.query_expenses(7).recv(program_id).await.unwrap(), None);
+    assert_eq!(service.query_payments(7).recv(program_id).await.unwrap(), None);
+    assert_eq!(service.query_balances(7).recv(program_id).await.unwrap(), None);
+    assert_eq!(service.query_settlement_plan(7).recv(program_id).await.unwrap(), None);
+    assert_eq!(service.query_category_totals(7).recv(program_id).await.unwrap(), None);
+    assert_eq!(service.query_budget_status(7).recv(program_id).await.unwrap(), None);
+    assert_eq!(service.query_recurring_expenses(7).recv(program_id).await.unwrap(), None);
+    assert_eq!(service.query_disputes(7).recv(program_id).await.unwrap(), None);
+    assert_eq!(service.query_pool(7).recv(program_id).await.unwrap(), None);
+    assert_eq!(service.query_archived_group(7).recv(program_id).await.unwrap(), None);
+    assert_eq!(service.query_member_statement(7, ALICE.into()).recv(program_id).await.unwrap(), None);
+    assert!(service.query_withdrawals(7).recv(program_id).await.unwrap().is_empty());
+
+    // The program panics when the group does not exist
+    assert!(service.query_group(7).recv(program_id).await.is_err());
+}
+
+#[tokio::test]
+async fn program_queries_work() {
+    let fixture = Fixture::new().await;
+    let program_id = fixture.program_id;
+
+    let config = fixture.service(ALICE).query_config().recv(program_id).await.unwrap();
+    assert_eq!(config.admin, ADMIN.into());
+
+    let snapshot = fixture.service(ALICE).export_state().recv(program_id).await.unwrap();
+    assert!(!snapshot.is_empty());
+}
+
+#[tokio::test]
+async fn snapshot_restores_groups_and_ids() {
+    let fixture = Fixture::new().await;
+    let group_id = group_with_members(&fixture).await;
+    let reply = add_expense(&fixture, ALICE, group_id, expense_dto(300, &[])).await;
+    let Events::ExpenseAdded((_, expense_id)) = reply else {
+        panic!("Unexpected reply: {reply:?}");
+    };
+
+    let snapshot = fixture.service(ADMIN).export_state().recv(fixture.program_id).await.unwrap();
+
+    let restored_id = AppFactory::new(fixture.remoting().clone())
+        .new_from_snapshot(snapshot)
+        .send_recv(fixture.code_id, b"restored")
+        .await
+        .expect("Failed to restore the snapshot");
+
+    let members = fixture.service(ALICE)
+        .query_group_members(group_id)
+        .recv(restored_id)
+        .await
+        .unwrap();
+    assert_eq!(members.map(|m| m.len()), Some(3));
+
+    // New ids continue after the ids of the restored state
+    let reply = fixture.service(BOB)
+        .create_group("Flat".to_owned())
+        .send_recv(restored_id)
+        .await
+        .unwrap();
+    assert_eq!(reply, Events::GroupCreated(expense_id + 1));
+}
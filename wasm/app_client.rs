@@ -22,6 +22,15 @@ impl<R: Remoting + Clone> traits::AppFactory for AppFactory<R> {
     fn new(&self) -> impl Activation<Args = R::Args> {
         RemotingAction::<_, app_factory::io::New>::new(self.remoting.clone(), ())
     }
+    fn new_from_snapshot(&self, snapshot: Vec<u8>) -> impl Activation<Args = R::Args> {
+        RemotingAction::<_, app_factory::io::NewFromSnapshot>::new(self.remoting.clone(), snapshot)
+    }
+    fn new_with_admin(&self, admin: ActorId) -> impl Activation<Args = R::Args> {
+        RemotingAction::<_, app_factory::io::NewWithAdmin>::new(self.remoting.clone(), admin)
+    }
+    fn new_with_config(&self, config: Config) -> impl Activation<Args = R::Args> {
+        RemotingAction::<_, app_factory::io::NewWithConfig>::new(self.remoting.clone(), config)
+    }
 }
 
 pub mod app_factory {
@@ -41,6 +50,47 @@ pub mod app_factory {
             type Params = ();
             type Reply = ();
         }
+        pub struct NewFromSnapshot(());
+        impl NewFromSnapshot {
+            #[allow(dead_code)]
+            pub fn encode_call(snapshot: Vec<u8>) -> Vec<u8> {
+                <NewFromSnapshot as ActionIo>::encode_call(&snapshot)
+            }
+        }
+        impl ActionIo for NewFromSnapshot {
+            const ROUTE: &'static [u8] = &[
+                60, 78, 101, 119, 70, 114, 111, 109, 83, 110, 97, 112, 115, 104, 111, 116,
+            ];
+            type Params = Vec<u8>;
+            type Reply = ();
+        }
+        pub struct NewWithAdmin(());
+        impl NewWithAdmin {
+            #[allow(dead_code)]
+            pub fn encode_call(admin: ActorId) -> Vec<u8> {
+                <NewWithAdmin as ActionIo>::encode_call(&admin)
+            }
+        }
+        impl ActionIo for NewWithAdmin {
+            const ROUTE: &'static [u8] =
+                &[48, 78, 101, 119, 87, 105, 116, 104, 65, 100, 109, 105, 110];
+            type Params = ActorId;
+            type Reply = ();
+        }
+        pub struct NewWithConfig(());
+        impl NewWithConfig {
+            #[allow(dead_code)]
+            pub fn encode_call(config: super::Config) -> Vec<u8> {
+                <NewWithConfig as ActionIo>::encode_call(&config)
+            }
+        }
+        impl ActionIo for NewWithConfig {
+            const ROUTE: &'static [u8] = &[
+                52, 78, 101, 119, 87, 105, 116, 104, 67, 111, 110, 102, 105, 103,
+            ];
+            type Params = super::Config;
+            type Reply = ();
+        }
     }
 }
 pub struct Service<R> {
@@ -53,6 +103,13 @@ impl<R> Service<R> {
 }
 impl<R: Remoting + Clone> traits::Service for Service<R> {
     type Args = R::Args;
+    fn add_admin(
+        &mut self,
+        group_id: u32,
+        member: ActorId,
+    ) -> impl Call<Output = Events, Args = R::Args> {
+        RemotingAction::<_, service::io::AddAdmin>::new(self.remoting.clone(), (group_id, member))
+    }
     fn add_expense(
         &mut self,
         group_id: u32,
@@ -63,6 +120,16 @@ impl<R: Remoting + Clone> traits::Service for Service<R> {
             (group_id, expenseDTO),
         )
     }
+    fn add_expenses_batch(
+        &mut self,
+        group_id: u32,
+        expenses: Vec<ExpenseDto>,
+    ) -> impl Call<Output = Events, Args = R::Args> {
+        RemotingAction::<_, service::io::AddExpensesBatch>::new(
+            self.remoting.clone(),
+            (group_id, expenses),
+        )
+    }
     fn add_payment(
         &mut self,
         group_id: u32,
@@ -74,18 +141,291 @@ impl<R: Remoting + Clone> traits::Service for Service<R> {
             (group_id, amount, to),
         )
     }
+    fn approve_expense(
+        &mut self,
+        group_id: u32,
+        expense_id: u32,
+    ) -> impl Call<Output = Events, Args = R::Args> {
+        RemotingAction::<_, service::io::ApproveExpense>::new(
+            self.remoting.clone(),
+            (group_id, expense_id),
+        )
+    }
+    fn approve_withdrawal(
+        &mut self,
+        proposal_id: u32,
+    ) -> impl Call<Output = Events, Args = R::Args> {
+        RemotingAction::<_, service::io::ApproveWithdrawal>::new(self.remoting.clone(), proposal_id)
+    }
+    fn batch(
+        &mut self,
+        group_id: u32,
+        actions: Vec<GroupAction>,
+    ) -> impl Call<Output = Events, Args = R::Args> {
+        RemotingAction::<_, service::io::Batch>::new(self.remoting.clone(), (group_id, actions))
+    }
+    fn cancel_recurring_expense(
+        &mut self,
+        group_id: u32,
+        recurring_id: u32,
+    ) -> impl Call<Output = Events, Args = R::Args> {
+        RemotingAction::<_, service::io::CancelRecurringExpense>::new(
+            self.remoting.clone(),
+            (group_id, recurring_id),
+        )
+    }
+    fn close_group(
+        &mut self,
+        group_id: u32,
+        force: bool,
+    ) -> impl Call<Output = Events, Args = R::Args> {
+        RemotingAction::<_, service::io::CloseGroup>::new(self.remoting.clone(), (group_id, force))
+    }
+    fn confirm_payment(
+        &mut self,
+        group_id: u32,
+        payment_id: u32,
+    ) -> impl Call<Output = Events, Args = R::Args> {
+        RemotingAction::<_, service::io::ConfirmPayment>::new(
+            self.remoting.clone(),
+            (group_id, payment_id),
+        )
+    }
     fn create_group(&mut self, group_name: String) -> impl Call<Output = Events, Args = R::Args> {
         RemotingAction::<_, service::io::CreateGroup>::new(self.remoting.clone(), group_name)
     }
+    fn create_recurring_expense(
+        &mut self,
+        group_id: u32,
+        expense_dto: ExpenseDto,
+        period_blocks: u32,
+        occurrences: u32,
+    ) -> impl Call<Output = Events, Args = R::Args> {
+        RemotingAction::<_, service::io::CreateRecurringExpense>::new(
+            self.remoting.clone(),
+            (group_id, expense_dto, period_blocks, occurrences),
+        )
+    }
+    fn deposit(&mut self, group_id: u32) -> impl Call<Output = Events, Args = R::Args> {
+        RemotingAction::<_, service::io::Deposit>::new(self.remoting.clone(), group_id)
+    }
+    fn execute_withdrawal(
+        &mut self,
+        proposal_id: u32,
+    ) -> impl Call<Output = Events, Args = R::Args> {
+        RemotingAction::<_, service::io::ExecuteWithdrawal>::new(self.remoting.clone(), proposal_id)
+    }
     fn join_group(&mut self, group_id: u32) -> impl Call<Output = Events, Args = R::Args> {
         RemotingAction::<_, service::io::JoinGroup>::new(self.remoting.clone(), group_id)
     }
+    fn open_dispute(
+        &mut self,
+        group_id: u32,
+        expense_id: u32,
+        reason: String,
+    ) -> impl Call<Output = Events, Args = R::Args> {
+        RemotingAction::<_, service::io::OpenDispute>::new(
+            self.remoting.clone(),
+            (group_id, expense_id, reason),
+        )
+    }
+    fn pause(&mut self) -> impl Call<Output = Events, Args = R::Args> {
+        RemotingAction::<_, service::io::Pause>::new(self.remoting.clone(), ())
+    }
+    fn pause_recurring_expense(
+        &mut self,
+        group_id: u32,
+        recurring_id: u32,
+    ) -> impl Call<Output = Events, Args = R::Args> {
+        RemotingAction::<_, service::io::PauseRecurringExpense>::new(
+            self.remoting.clone(),
+            (group_id, recurring_id),
+        )
+    }
+    fn pay_from_pool(
+        &mut self,
+        group_id: u32,
+        vendor: ActorId,
+        amount: u128,
+        description: String,
+    ) -> impl Call<Output = Events, Args = R::Args> {
+        RemotingAction::<_, service::io::PayFromPool>::new(
+            self.remoting.clone(),
+            (group_id, vendor, amount, description),
+        )
+    }
+    fn process_recurring_expense(
+        &mut self,
+        group_id: u32,
+        recurring_id: u32,
+        nonce: u32,
+    ) -> impl Call<Output = Events, Args = R::Args> {
+        RemotingAction::<_, service::io::ProcessRecurringExpense>::new(
+            self.remoting.clone(),
+            (group_id, recurring_id, nonce),
+        )
+    }
+    fn propose_withdrawal(
+        &mut self,
+        group_id: u32,
+        to: ActorId,
+        amount: u128,
+        expiry_blocks: u32,
+    ) -> impl Call<Output = Events, Args = R::Args> {
+        RemotingAction::<_, service::io::ProposeWithdrawal>::new(
+            self.remoting.clone(),
+            (group_id, to, amount, expiry_blocks),
+        )
+    }
+    fn reject_expense(
+        &mut self,
+        group_id: u32,
+        expense_id: u32,
+    ) -> impl Call<Output = Events, Args = R::Args> {
+        RemotingAction::<_, service::io::RejectExpense>::new(
+            self.remoting.clone(),
+            (group_id, expense_id),
+        )
+    }
+    fn reject_payment(
+        &mut self,
+        group_id: u32,
+        payment_id: u32,
+    ) -> impl Call<Output = Events, Args = R::Args> {
+        RemotingAction::<_, service::io::RejectPayment>::new(
+            self.remoting.clone(),
+            (group_id, payment_id),
+        )
+    }
+    fn remove_budget(
+        &mut self,
+        group_id: u32,
+        category: Option<ExpenseCategory>,
+        currency: String,
+    ) -> impl Call<Output = Events, Args = R::Args> {
+        RemotingAction::<_, service::io::RemoveBudget>::new(
+            self.remoting.clone(),
+            (group_id, category, currency),
+        )
+    }
+    fn resolve_dispute(
+        &mut self,
+        group_id: u32,
+        dispute_id: u32,
+        resolution: DisputeResolution,
+    ) -> impl Call<Output = Events, Args = R::Args> {
+        RemotingAction::<_, service::io::ResolveDispute>::new(
+            self.remoting.clone(),
+            (group_id, dispute_id, resolution),
+        )
+    }
+    fn resume_recurring_expense(
+        &mut self,
+        group_id: u32,
+        recurring_id: u32,
+    ) -> impl Call<Output = Events, Args = R::Args> {
+        RemotingAction::<_, service::io::ResumeRecurringExpense>::new(
+            self.remoting.clone(),
+            (group_id, recurring_id),
+        )
+    }
+    fn set_approval_policy(
+        &mut self,
+        group_id: u32,
+        threshold: Option<u128>,
+        required_approvals: u32,
+    ) -> impl Call<Output = Events, Args = R::Args> {
+        RemotingAction::<_, service::io::SetApprovalPolicy>::new(
+            self.remoting.clone(),
+            (group_id, threshold, required_approvals),
+        )
+    }
+    fn set_budget(
+        &mut self,
+        group_id: u32,
+        category: Option<ExpenseCategory>,
+        currency: String,
+        limit: u128,
+        period_blocks: u32,
+        thresholds: Vec<u8>,
+    ) -> impl Call<Output = Events, Args = R::Args> {
+        RemotingAction::<_, service::io::SetBudget>::new(
+            self.remoting.clone(),
+            (
+                group_id,
+                category,
+                currency,
+                limit,
+                period_blocks,
+                thresholds,
+            ),
+        )
+    }
+    fn set_withdrawal_quorum(
+        &mut self,
+        group_id: u32,
+        quorum: u32,
+    ) -> impl Call<Output = Events, Args = R::Args> {
+        RemotingAction::<_, service::io::SetWithdrawalQuorum>::new(
+            self.remoting.clone(),
+            (group_id, quorum),
+        )
+    }
+    fn unpause(&mut self) -> impl Call<Output = Events, Args = R::Args> {
+        RemotingAction::<_, service::io::Unpause>::new(self.remoting.clone(), ())
+    }
+    fn update_group_metadata(
+        &mut self,
+        group_id: u32,
+        metadata: GroupMetadataDto,
+    ) -> impl Call<Output = Events, Args = R::Args> {
+        RemotingAction::<_, service::io::UpdateGroupMetadata>::new(
+            self.remoting.clone(),
+            (group_id, metadata),
+        )
+    }
+    fn export_state(&self) -> impl Query<Output = Vec<u8>, Args = R::Args> {
+        RemotingAction::<_, service::io::ExportState>::new(self.remoting.clone(), ())
+    }
     fn query(&self) -> impl Query<Output = IoState, Args = R::Args> {
         RemotingAction::<_, service::io::Query>::new(self.remoting.clone(), ())
     }
     fn query_actor_groups(&self) -> impl Query<Output = Vec<Group>, Args = R::Args> {
         RemotingAction::<_, service::io::QueryActorGroups>::new(self.remoting.clone(), ())
     }
+    fn query_archived_group(
+        &self,
+        group_id: u32,
+    ) -> impl Query<Output = Option<ArchivedGroup>, Args = R::Args> {
+        RemotingAction::<_, service::io::QueryArchivedGroup>::new(self.remoting.clone(), group_id)
+    }
+    fn query_balances(
+        &self,
+        group_id: u32,
+    ) -> impl Query<Output = Option<Vec<Balance>>, Args = R::Args> {
+        RemotingAction::<_, service::io::QueryBalances>::new(self.remoting.clone(), group_id)
+    }
+    fn query_budget_status(
+        &self,
+        group_id: u32,
+    ) -> impl Query<Output = Option<Vec<BudgetStatus>>, Args = R::Args> {
+        RemotingAction::<_, service::io::QueryBudgetStatus>::new(self.remoting.clone(), group_id)
+    }
+    fn query_category_totals(
+        &self,
+        group_id: u32,
+    ) -> impl Query<Output = Option<Vec<CategoryTotal>>, Args = R::Args> {
+        RemotingAction::<_, service::io::QueryCategoryTotals>::new(self.remoting.clone(), group_id)
+    }
+    fn query_config(&self) -> impl Query<Output = Config, Args = R::Args> {
+        RemotingAction::<_, service::io::QueryConfig>::new(self.remoting.clone(), ())
+    }
+    fn query_disputes(
+        &self,
+        group_id: u32,
+    ) -> impl Query<Output = Option<Vec<Dispute>>, Args = R::Args> {
+        RemotingAction::<_, service::io::QueryDisputes>::new(self.remoting.clone(), group_id)
+    }
     fn query_expenses(
         &self,
         group_id: u32,
@@ -101,6 +441,49 @@ impl<R: Remoting + Clone> traits::Service for Service<R> {
     ) -> impl Query<Output = Option<Vec<ActorId>>, Args = R::Args> {
         RemotingAction::<_, service::io::QueryGroupMembers>::new(self.remoting.clone(), group_id)
     }
+    fn query_member_statement(
+        &self,
+        group_id: u32,
+        actor_id: ActorId,
+    ) -> impl Query<Output = Option<MemberStatement>, Args = R::Args> {
+        RemotingAction::<_, service::io::QueryMemberStatement>::new(
+            self.remoting.clone(),
+            (group_id, actor_id),
+        )
+    }
+    fn query_payments(
+        &self,
+        group_id: u32,
+    ) -> impl Query<Output = Option<Vec<Payment>>, Args = R::Args> {
+        RemotingAction::<_, service::io::QueryPayments>::new(self.remoting.clone(), group_id)
+    }
+    fn query_pool(
+        &self,
+        group_id: u32,
+    ) -> impl Query<Output = Option<Vec<PoolBalance>>, Args = R::Args> {
+        RemotingAction::<_, service::io::QueryPool>::new(self.remoting.clone(), group_id)
+    }
+    fn query_recurring_expenses(
+        &self,
+        group_id: u32,
+    ) -> impl Query<Output = Option<Vec<RecurringExpense>>, Args = R::Args> {
+        RemotingAction::<_, service::io::QueryRecurringExpenses>::new(
+            self.remoting.clone(),
+            group_id,
+        )
+    }
+    fn query_settlement_plan(
+        &self,
+        group_id: u32,
+    ) -> impl Query<Output = Option<Vec<Transfer>>, Args = R::Args> {
+        RemotingAction::<_, service::io::QuerySettlementPlan>::new(self.remoting.clone(), group_id)
+    }
+    fn query_withdrawals(
+        &self,
+        group_id: u32,
+    ) -> impl Query<Output = Vec<WithdrawalProposal>, Args = R::Args> {
+        RemotingAction::<_, service::io::QueryWithdrawals>::new(self.remoting.clone(), group_id)
+    }
 }
 
 pub mod service {
@@ -109,6 +492,20 @@ pub mod service {
     pub mod io {
         use super::*;
         use sails_rs::calls::ActionIo;
+        pub struct AddAdmin(());
+        impl AddAdmin {
+            #[allow(dead_code)]
+            pub fn encode_call(group_id: u32, member: ActorId) -> Vec<u8> {
+                <AddAdmin as ActionIo>::encode_call(&(group_id, member))
+            }
+        }
+        impl ActionIo for AddAdmin {
+            const ROUTE: &'static [u8] = &[
+                28, 83, 101, 114, 118, 105, 99, 101, 32, 65, 100, 100, 65, 100, 109, 105, 110,
+            ];
+            type Params = (u32, ActorId);
+            type Reply = super::Events;
+        }
         pub struct AddExpense(());
         impl AddExpense {
             #[allow(dead_code)]
@@ -124,6 +521,21 @@ pub mod service {
             type Params = (u32, super::ExpenseDto);
             type Reply = super::Events;
         }
+        pub struct AddExpensesBatch(());
+        impl AddExpensesBatch {
+            #[allow(dead_code)]
+            pub fn encode_call(group_id: u32, expenses: Vec<super::ExpenseDto>) -> Vec<u8> {
+                <AddExpensesBatch as ActionIo>::encode_call(&(group_id, expenses))
+            }
+        }
+        impl ActionIo for AddExpensesBatch {
+            const ROUTE: &'static [u8] = &[
+                28, 83, 101, 114, 118, 105, 99, 101, 64, 65, 100, 100, 69, 120, 112, 101, 110, 115,
+                101, 115, 66, 97, 116, 99, 104,
+            ];
+            type Params = (u32, Vec<super::ExpenseDto>);
+            type Reply = super::Events;
+        }
         pub struct AddPayment(());
         impl AddPayment {
             #[allow(dead_code)]
@@ -139,80 +551,612 @@ pub mod service {
             type Params = (u32, u32, ActorId);
             type Reply = super::Events;
         }
-        pub struct CreateGroup(());
-        impl CreateGroup {
+        pub struct ApproveExpense(());
+        impl ApproveExpense {
             #[allow(dead_code)]
-            pub fn encode_call(group_name: String) -> Vec<u8> {
-                <CreateGroup as ActionIo>::encode_call(&group_name)
+            pub fn encode_call(group_id: u32, expense_id: u32) -> Vec<u8> {
+                <ApproveExpense as ActionIo>::encode_call(&(group_id, expense_id))
             }
         }
-        impl ActionIo for CreateGroup {
+        impl ActionIo for ApproveExpense {
             const ROUTE: &'static [u8] = &[
-                28, 83, 101, 114, 118, 105, 99, 101, 44, 67, 114, 101, 97, 116, 101, 71, 114, 111,
-                117, 112,
+                28, 83, 101, 114, 118, 105, 99, 101, 56, 65, 112, 112, 114, 111, 118, 101, 69, 120,
+                112, 101, 110, 115, 101,
             ];
-            type Params = String;
+            type Params = (u32, u32);
             type Reply = super::Events;
         }
-        pub struct JoinGroup(());
-        impl JoinGroup {
+        pub struct ApproveWithdrawal(());
+        impl ApproveWithdrawal {
             #[allow(dead_code)]
-            pub fn encode_call(group_id: u32) -> Vec<u8> {
-                <JoinGroup as ActionIo>::encode_call(&group_id)
+            pub fn encode_call(proposal_id: u32) -> Vec<u8> {
+                <ApproveWithdrawal as ActionIo>::encode_call(&proposal_id)
             }
         }
-        impl ActionIo for JoinGroup {
+        impl ActionIo for ApproveWithdrawal {
             const ROUTE: &'static [u8] = &[
-                28, 83, 101, 114, 118, 105, 99, 101, 36, 74, 111, 105, 110, 71, 114, 111, 117, 112,
+                28, 83, 101, 114, 118, 105, 99, 101, 68, 65, 112, 112, 114, 111, 118, 101, 87, 105,
+                116, 104, 100, 114, 97, 119, 97, 108,
             ];
             type Params = u32;
             type Reply = super::Events;
         }
-        pub struct Query(());
-        impl Query {
+        pub struct Batch(());
+        impl Batch {
             #[allow(dead_code)]
-            pub fn encode_call() -> Vec<u8> {
-                <Query as ActionIo>::encode_call(&())
+            pub fn encode_call(group_id: u32, actions: Vec<super::GroupAction>) -> Vec<u8> {
+                <Batch as ActionIo>::encode_call(&(group_id, actions))
             }
         }
-        impl ActionIo for Query {
+        impl ActionIo for Batch {
             const ROUTE: &'static [u8] = &[
-                28, 83, 101, 114, 118, 105, 99, 101, 20, 81, 117, 101, 114, 121,
+                28, 83, 101, 114, 118, 105, 99, 101, 20, 66, 97, 116, 99, 104,
             ];
-            type Params = ();
-            type Reply = super::IoState;
+            type Params = (u32, Vec<super::GroupAction>);
+            type Reply = super::Events;
         }
-        pub struct QueryActorGroups(());
-        impl QueryActorGroups {
+        pub struct CancelRecurringExpense(());
+        impl CancelRecurringExpense {
             #[allow(dead_code)]
-            pub fn encode_call() -> Vec<u8> {
-                <QueryActorGroups as ActionIo>::encode_call(&())
+            pub fn encode_call(group_id: u32, recurring_id: u32) -> Vec<u8> {
+                <CancelRecurringExpense as ActionIo>::encode_call(&(group_id, recurring_id))
             }
         }
-        impl ActionIo for QueryActorGroups {
+        impl ActionIo for CancelRecurringExpense {
             const ROUTE: &'static [u8] = &[
-                28, 83, 101, 114, 118, 105, 99, 101, 64, 81, 117, 101, 114, 121, 65, 99, 116, 111,
-                114, 71, 114, 111, 117, 112, 115,
+                28, 83, 101, 114, 118, 105, 99, 101, 88, 67, 97, 110, 99, 101, 108, 82, 101, 99,
+                117, 114, 114, 105, 110, 103, 69, 120, 112, 101, 110, 115, 101,
             ];
-            type Params = ();
-            type Reply = Vec<super::Group>;
+            type Params = (u32, u32);
+            type Reply = super::Events;
         }
-        pub struct QueryExpenses(());
-        impl QueryExpenses {
+        pub struct CloseGroup(());
+        impl CloseGroup {
             #[allow(dead_code)]
-            pub fn encode_call(group_id: u32) -> Vec<u8> {
-                <QueryExpenses as ActionIo>::encode_call(&group_id)
+            pub fn encode_call(group_id: u32, force: bool) -> Vec<u8> {
+                <CloseGroup as ActionIo>::encode_call(&(group_id, force))
             }
         }
-        impl ActionIo for QueryExpenses {
+        impl ActionIo for CloseGroup {
             const ROUTE: &'static [u8] = &[
-                28, 83, 101, 114, 118, 105, 99, 101, 52, 81, 117, 101, 114, 121, 69, 120, 112, 101,
-                110, 115, 101, 115,
+                28, 83, 101, 114, 118, 105, 99, 101, 40, 67, 108, 111, 115, 101, 71, 114, 111, 117,
+                112,
             ];
-            type Params = u32;
-            type Reply = Option<Vec<super::Expense>>;
+            type Params = (u32, bool);
+            type Reply = super::Events;
         }
-        pub struct QueryGroup(());
+        pub struct ConfirmPayment(());
+        impl ConfirmPayment {
+            #[allow(dead_code)]
+            pub fn encode_call(group_id: u32, payment_id: u32) -> Vec<u8> {
+                <ConfirmPayment as ActionIo>::encode_call(&(group_id, payment_id))
+            }
+        }
+        impl ActionIo for ConfirmPayment {
+            const ROUTE: &'static [u8] = &[
+                28, 83, 101, 114, 118, 105, 99, 101, 56, 67, 111, 110, 102, 105, 114, 109, 80, 97,
+                121, 109, 101, 110, 116,
+            ];
+            type Params = (u32, u32);
+            type Reply = super::Events;
+        }
+        pub struct CreateGroup(());
+        impl CreateGroup {
+            #[allow(dead_code)]
+            pub fn encode_call(group_name: String) -> Vec<u8> {
+                <CreateGroup as ActionIo>::encode_call(&group_name)
+            }
+        }
+        impl ActionIo for CreateGroup {
+            const ROUTE: &'static [u8] = &[
+                28, 83, 101, 114, 118, 105, 99, 101, 44, 67, 114, 101, 97, 116, 101, 71, 114, 111,
+                117, 112,
+            ];
+            type Params = String;
+            type Reply = super::Events;
+        }
+        pub struct CreateRecurringExpense(());
+        impl CreateRecurringExpense {
+            #[allow(dead_code)]
+            pub fn encode_call(
+                group_id: u32,
+                expense_dto: super::ExpenseDto,
+                period_blocks: u32,
+                occurrences: u32,
+            ) -> Vec<u8> {
+                <CreateRecurringExpense as ActionIo>::encode_call(&(
+                    group_id,
+                    expense_dto,
+                    period_blocks,
+                    occurrences,
+                ))
+            }
+        }
+        impl ActionIo for CreateRecurringExpense {
+            const ROUTE: &'static [u8] = &[
+                28, 83, 101, 114, 118, 105, 99, 101, 88, 67, 114, 101, 97, 116, 101, 82, 101, 99,
+                117, 114, 114, 105, 110, 103, 69, 120, 112, 101, 110, 115, 101,
+            ];
+            type Params = (u32, super::ExpenseDto, u32, u32);
+            type Reply = super::Events;
+        }
+        pub struct Deposit(());
+        impl Deposit {
+            #[allow(dead_code)]
+            pub fn encode_call(group_id: u32) -> Vec<u8> {
+                <Deposit as ActionIo>::encode_call(&group_id)
+            }
+        }
+        impl ActionIo for Deposit {
+            const ROUTE: &'static [u8] = &[
+                28, 83, 101, 114, 118, 105, 99, 101, 28, 68, 101, 112, 111, 115, 105, 116,
+            ];
+            type Params = u32;
+            type Reply = super::Events;
+        }
+        pub struct ExecuteWithdrawal(());
+        impl ExecuteWithdrawal {
+            #[allow(dead_code)]
+            pub fn encode_call(proposal_id: u32) -> Vec<u8> {
+                <ExecuteWithdrawal as ActionIo>::encode_call(&proposal_id)
+            }
+        }
+        impl ActionIo for ExecuteWithdrawal {
+            const ROUTE: &'static [u8] = &[
+                28, 83, 101, 114, 118, 105, 99, 101, 68, 69, 120, 101, 99, 117, 116, 101, 87, 105,
+                116, 104, 100, 114, 97, 119, 97, 108,
+            ];
+            type Params = u32;
+            type Reply = super::Events;
+        }
+        pub struct JoinGroup(());
+        impl JoinGroup {
+            #[allow(dead_code)]
+            pub fn encode_call(group_id: u32) -> Vec<u8> {
+                <JoinGroup as ActionIo>::encode_call(&group_id)
+            }
+        }
+        impl ActionIo for JoinGroup {
+            const ROUTE: &'static [u8] = &[
+                28, 83, 101, 114, 118, 105, 99, 101, 36, 74, 111, 105, 110, 71, 114, 111, 117, 112,
+            ];
+            type Params = u32;
+            type Reply = super::Events;
+        }
+        pub struct OpenDispute(());
+        impl OpenDispute {
+            #[allow(dead_code)]
+            pub fn encode_call(group_id: u32, expense_id: u32, reason: String) -> Vec<u8> {
+                <OpenDispute as ActionIo>::encode_call(&(group_id, expense_id, reason))
+            }
+        }
+        impl ActionIo for OpenDispute {
+            const ROUTE: &'static [u8] = &[
+                28, 83, 101, 114, 118, 105, 99, 101, 44, 79, 112, 101, 110, 68, 105, 115, 112, 117,
+                116, 101,
+            ];
+            type Params = (u32, u32, String);
+            type Reply = super::Events;
+        }
+        pub struct Pause(());
+        impl Pause {
+            #[allow(dead_code)]
+            pub fn encode_call() -> Vec<u8> {
+                <Pause as ActionIo>::encode_call(&())
+            }
+        }
+        impl ActionIo for Pause {
+            const ROUTE: &'static [u8] = &[
+                28, 83, 101, 114, 118, 105, 99, 101, 20, 80, 97, 117, 115, 101,
+            ];
+            type Params = ();
+            type Reply = super::Events;
+        }
+        pub struct PauseRecurringExpense(());
+        impl PauseRecurringExpense {
+            #[allow(dead_code)]
+            pub fn encode_call(group_id: u32, recurring_id: u32) -> Vec<u8> {
+                <PauseRecurringExpense as ActionIo>::encode_call(&(group_id, recurring_id))
+            }
+        }
+        impl ActionIo for PauseRecurringExpense {
+            const ROUTE: &'static [u8] = &[
+                28, 83, 101, 114, 118, 105, 99, 101, 84, 80, 97, 117, 115, 101, 82, 101, 99, 117,
+                114, 114, 105, 110, 103, 69, 120, 112, 101, 110, 115, 101,
+            ];
+            type Params = (u32, u32);
+            type Reply = super::Events;
+        }
+        pub struct PayFromPool(());
+        impl PayFromPool {
+            #[allow(dead_code)]
+            pub fn encode_call(
+                group_id: u32,
+                vendor: ActorId,
+                amount: u128,
+                description: String,
+            ) -> Vec<u8> {
+                <PayFromPool as ActionIo>::encode_call(&(group_id, vendor, amount, description))
+            }
+        }
+        impl ActionIo for PayFromPool {
+            const ROUTE: &'static [u8] = &[
+                28, 83, 101, 114, 118, 105, 99, 101, 44, 80, 97, 121, 70, 114, 111, 109, 80, 111,
+                111, 108,
+            ];
+            type Params = (u32, ActorId, u128, String);
+            type Reply = super::Events;
+        }
+        pub struct ProcessRecurringExpense(());
+        impl ProcessRecurringExpense {
+            #[allow(dead_code)]
+            pub fn encode_call(group_id: u32, recurring_id: u32, nonce: u32) -> Vec<u8> {
+                <ProcessRecurringExpense as ActionIo>::encode_call(&(group_id, recurring_id, nonce))
+            }
+        }
+        impl ActionIo for ProcessRecurringExpense {
+            const ROUTE: &'static [u8] = &[
+                28, 83, 101, 114, 118, 105, 99, 101, 92, 80, 114, 111, 99, 101, 115, 115, 82, 101,
+                99, 117, 114, 114, 105, 110, 103, 69, 120, 112, 101, 110, 115, 101,
+            ];
+            type Params = (u32, u32, u32);
+            type Reply = super::Events;
+        }
+        pub struct ProposeWithdrawal(());
+        impl ProposeWithdrawal {
+            #[allow(dead_code)]
+            pub fn encode_call(
+                group_id: u32,
+                to: ActorId,
+                amount: u128,
+                expiry_blocks: u32,
+            ) -> Vec<u8> {
+                <ProposeWithdrawal as ActionIo>::encode_call(&(group_id, to, amount, expiry_blocks))
+            }
+        }
+        impl ActionIo for ProposeWithdrawal {
+            const ROUTE: &'static [u8] = &[
+                28, 83, 101, 114, 118, 105, 99, 101, 68, 80, 114, 111, 112, 111, 115, 101, 87, 105,
+                116, 104, 100, 114, 97, 119, 97, 108,
+            ];
+            type Params = (u32, ActorId, u128, u32);
+            type Reply = super::Events;
+        }
+        pub struct RejectExpense(());
+        impl RejectExpense {
+            #[allow(dead_code)]
+            pub fn encode_call(group_id: u32, expense_id: u32) -> Vec<u8> {
+                <RejectExpense as ActionIo>::encode_call(&(group_id, expense_id))
+            }
+        }
+        impl ActionIo for RejectExpense {
+            const ROUTE: &'static [u8] = &[
+                28, 83, 101, 114, 118, 105, 99, 101, 52, 82, 101, 106, 101, 99, 116, 69, 120, 112,
+                101, 110, 115, 101,
+            ];
+            type Params = (u32, u32);
+            type Reply = super::Events;
+        }
+        pub struct RejectPayment(());
+        impl RejectPayment {
+            #[allow(dead_code)]
+            pub fn encode_call(group_id: u32, payment_id: u32) -> Vec<u8> {
+                <RejectPayment as ActionIo>::encode_call(&(group_id, payment_id))
+            }
+        }
+        impl ActionIo for RejectPayment {
+            const ROUTE: &'static [u8] = &[
+                28, 83, 101, 114, 118, 105, 99, 101, 52, 82, 101, 106, 101, 99, 116, 80, 97, 121,
+                109, 101, 110, 116,
+            ];
+            type Params = (u32, u32);
+            type Reply = super::Events;
+        }
+        pub struct RemoveBudget(());
+        impl RemoveBudget {
+            #[allow(dead_code)]
+            pub fn encode_call(
+                group_id: u32,
+                category: Option<super::ExpenseCategory>,
+                currency: String,
+            ) -> Vec<u8> {
+                <RemoveBudget as ActionIo>::encode_call(&(group_id, category, currency))
+            }
+        }
+        impl ActionIo for RemoveBudget {
+            const ROUTE: &'static [u8] = &[
+                28, 83, 101, 114, 118, 105, 99, 101, 48, 82, 101, 109, 111, 118, 101, 66, 117, 100,
+                103, 101, 116,
+            ];
+            type Params = (u32, Option<super::ExpenseCategory>, String);
+            type Reply = super::Events;
+        }
+        pub struct ResolveDispute(());
+        impl ResolveDispute {
+            #[allow(dead_code)]
+            pub fn encode_call(
+                group_id: u32,
+                dispute_id: u32,
+                resolution: super::DisputeResolution,
+            ) -> Vec<u8> {
+                <ResolveDispute as ActionIo>::encode_call(&(group_id, dispute_id, resolution))
+            }
+        }
+        impl ActionIo for ResolveDispute {
+            const ROUTE: &'static [u8] = &[
+                28, 83, 101, 114, 118, 105, 99, 101, 56, 82, 101, 115, 111, 108, 118, 101, 68, 105,
+                115, 112, 117, 116, 101,
+            ];
+            type Params = (u32, u32, super::DisputeResolution);
+            type Reply = super::Events;
+        }
+        pub struct ResumeRecurringExpense(());
+        impl ResumeRecurringExpense {
+            #[allow(dead_code)]
+            pub fn encode_call(group_id: u32, recurring_id: u32) -> Vec<u8> {
+                <ResumeRecurringExpense as ActionIo>::encode_call(&(group_id, recurring_id))
+            }
+        }
+        impl ActionIo for ResumeRecurringExpense {
+            const ROUTE: &'static [u8] = &[
+                28, 83, 101, 114, 118, 105, 99, 101, 88, 82, 101, 115, 117, 109, 101, 82, 101, 99,
+                117, 114, 114, 105, 110, 103, 69, 120, 112, 101, 110, 115, 101,
+            ];
+            type Params = (u32, u32);
+            type Reply = super::Events;
+        }
+        pub struct SetApprovalPolicy(());
+        impl SetApprovalPolicy {
+            #[allow(dead_code)]
+            pub fn encode_call(
+                group_id: u32,
+                threshold: Option<u128>,
+                required_approvals: u32,
+            ) -> Vec<u8> {
+                <SetApprovalPolicy as ActionIo>::encode_call(&(
+                    group_id,
+                    threshold,
+                    required_approvals,
+                ))
+            }
+        }
+        impl ActionIo for SetApprovalPolicy {
+            const ROUTE: &'static [u8] = &[
+                28, 83, 101, 114, 118, 105, 99, 101, 68, 83, 101, 116, 65, 112, 112, 114, 111, 118,
+                97, 108, 80, 111, 108, 105, 99, 121,
+            ];
+            type Params = (u32, Option<u128>, u32);
+            type Reply = super::Events;
+        }
+        pub struct SetBudget(());
+        impl SetBudget {
+            #[allow(dead_code)]
+            pub fn encode_call(
+                group_id: u32,
+                category: Option<super::ExpenseCategory>,
+                currency: String,
+                limit: u128,
+                period_blocks: u32,
+                thresholds: Vec<u8>,
+            ) -> Vec<u8> {
+                <SetBudget as ActionIo>::encode_call(&(
+                    group_id,
+                    category,
+                    currency,
+                    limit,
+                    period_blocks,
+                    thresholds,
+                ))
+            }
+        }
+        impl ActionIo for SetBudget {
+            const ROUTE: &'static [u8] = &[
+                28, 83, 101, 114, 118, 105, 99, 101, 36, 83, 101, 116, 66, 117, 100, 103, 101, 116,
+            ];
+            type Params = (
+                u32,
+                Option<super::ExpenseCategory>,
+                String,
+                u128,
+                u32,
+                Vec<u8>,
+            );
+            type Reply = super::Events;
+        }
+        pub struct SetWithdrawalQuorum(());
+        impl SetWithdrawalQuorum {
+            #[allow(dead_code)]
+            pub fn encode_call(group_id: u32, quorum: u32) -> Vec<u8> {
+                <SetWithdrawalQuorum as ActionIo>::encode_call(&(group_id, quorum))
+            }
+        }
+        impl ActionIo for SetWithdrawalQuorum {
+            const ROUTE: &'static [u8] = &[
+                28, 83, 101, 114, 118, 105, 99, 101, 76, 83, 101, 116, 87, 105, 116, 104, 100, 114,
+                97, 119, 97, 108, 81, 117, 111, 114, 117, 109,
+            ];
+            type Params = (u32, u32);
+            type Reply = super::Events;
+        }
+        pub struct Unpause(());
+        impl Unpause {
+            #[allow(dead_code)]
+            pub fn encode_call() -> Vec<u8> {
+                <Unpause as ActionIo>::encode_call(&())
+            }
+        }
+        impl ActionIo for Unpause {
+            const ROUTE: &'static [u8] = &[
+                28, 83, 101, 114, 118, 105, 99, 101, 28, 85, 110, 112, 97, 117, 115, 101,
+            ];
+            type Params = ();
+            type Reply = super::Events;
+        }
+        pub struct UpdateGroupMetadata(());
+        impl UpdateGroupMetadata {
+            #[allow(dead_code)]
+            pub fn encode_call(group_id: u32, metadata: super::GroupMetadataDto) -> Vec<u8> {
+                <UpdateGroupMetadata as ActionIo>::encode_call(&(group_id, metadata))
+            }
+        }
+        impl ActionIo for UpdateGroupMetadata {
+            const ROUTE: &'static [u8] = &[
+                28, 83, 101, 114, 118, 105, 99, 101, 76, 85, 112, 100, 97, 116, 101, 71, 114, 111,
+                117, 112, 77, 101, 116, 97, 100, 97, 116, 97,
+            ];
+            type Params = (u32, super::GroupMetadataDto);
+            type Reply = super::Events;
+        }
+        pub struct ExportState(());
+        impl ExportState {
+            #[allow(dead_code)]
+            pub fn encode_call() -> Vec<u8> {
+                <ExportState as ActionIo>::encode_call(&())
+            }
+        }
+        impl ActionIo for ExportState {
+            const ROUTE: &'static [u8] = &[
+                28, 83, 101, 114, 118, 105, 99, 101, 44, 69, 120, 112, 111, 114, 116, 83, 116, 97,
+                116, 101,
+            ];
+            type Params = ();
+            type Reply = Vec<u8>;
+        }
+        pub struct Query(());
+        impl Query {
+            #[allow(dead_code)]
+            pub fn encode_call() -> Vec<u8> {
+                <Query as ActionIo>::encode_call(&())
+            }
+        }
+        impl ActionIo for Query {
+            const ROUTE: &'static [u8] = &[
+                28, 83, 101, 114, 118, 105, 99, 101, 20, 81, 117, 101, 114, 121,
+            ];
+            type Params = ();
+            type Reply = super::IoState;
+        }
+        pub struct QueryActorGroups(());
+        impl QueryActorGroups {
+            #[allow(dead_code)]
+            pub fn encode_call() -> Vec<u8> {
+                <QueryActorGroups as ActionIo>::encode_call(&())
+            }
+        }
+        impl ActionIo for QueryActorGroups {
+            const ROUTE: &'static [u8] = &[
+                28, 83, 101, 114, 118, 105, 99, 101, 64, 81, 117, 101, 114, 121, 65, 99, 116, 111,
+                114, 71, 114, 111, 117, 112, 115,
+            ];
+            type Params = ();
+            type Reply = Vec<super::Group>;
+        }
+        pub struct QueryArchivedGroup(());
+        impl QueryArchivedGroup {
+            #[allow(dead_code)]
+            pub fn encode_call(group_id: u32) -> Vec<u8> {
+                <QueryArchivedGroup as ActionIo>::encode_call(&group_id)
+            }
+        }
+        impl ActionIo for QueryArchivedGroup {
+            const ROUTE: &'static [u8] = &[
+                28, 83, 101, 114, 118, 105, 99, 101, 72, 81, 117, 101, 114, 121, 65, 114, 99, 104,
+                105, 118, 101, 100, 71, 114, 111, 117, 112,
+            ];
+            type Params = u32;
+            type Reply = Option<super::ArchivedGroup>;
+        }
+        pub struct QueryBalances(());
+        impl QueryBalances {
+            #[allow(dead_code)]
+            pub fn encode_call(group_id: u32) -> Vec<u8> {
+                <QueryBalances as ActionIo>::encode_call(&group_id)
+            }
+        }
+        impl ActionIo for QueryBalances {
+            const ROUTE: &'static [u8] = &[
+                28, 83, 101, 114, 118, 105, 99, 101, 52, 81, 117, 101, 114, 121, 66, 97, 108, 97,
+                110, 99, 101, 115,
+            ];
+            type Params = u32;
+            type Reply = Option<Vec<super::Balance>>;
+        }
+        pub struct QueryBudgetStatus(());
+        impl QueryBudgetStatus {
+            #[allow(dead_code)]
+            pub fn encode_call(group_id: u32) -> Vec<u8> {
+                <QueryBudgetStatus as ActionIo>::encode_call(&group_id)
+            }
+        }
+        impl ActionIo for QueryBudgetStatus {
+            const ROUTE: &'static [u8] = &[
+                28, 83, 101, 114, 118, 105, 99, 101, 68, 81, 117, 101, 114, 121, 66, 117, 100, 103,
+                101, 116, 83, 116, 97, 116, 117, 115,
+            ];
+            type Params = u32;
+            type Reply = Option<Vec<super::BudgetStatus>>;
+        }
+        pub struct QueryCategoryTotals(());
+        impl QueryCategoryTotals {
+            #[allow(dead_code)]
+            pub fn encode_call(group_id: u32) -> Vec<u8> {
+                <QueryCategoryTotals as ActionIo>::encode_call(&group_id)
+            }
+        }
+        impl ActionIo for QueryCategoryTotals {
+            const ROUTE: &'static [u8] = &[
+                28, 83, 101, 114, 118, 105, 99, 101, 76, 81, 117, 101, 114, 121, 67, 97, 116, 101,
+                103, 111, 114, 121, 84, 111, 116, 97, 108, 115,
+            ];
+            type Params = u32;
+            type Reply = Option<Vec<super::CategoryTotal>>;
+        }
+        pub struct QueryConfig(());
+        impl QueryConfig {
+            #[allow(dead_code)]
+            pub fn encode_call() -> Vec<u8> {
+                <QueryConfig as ActionIo>::encode_call(&())
+            }
+        }
+        impl ActionIo for QueryConfig {
+            const ROUTE: &'static [u8] = &[
+                28, 83, 101, 114, 118, 105, 99, 101, 44, 81, 117, 101, 114, 121, 67, 111, 110, 102,
+                105, 103,
+            ];
+            type Params = ();
+            type Reply = super::Config;
+        }
+        pub struct QueryDisputes(());
+        impl QueryDisputes {
+            #[allow(dead_code)]
+            pub fn encode_call(group_id: u32) -> Vec<u8> {
+                <QueryDisputes as ActionIo>::encode_call(&group_id)
+            }
+        }
+        impl ActionIo for QueryDisputes {
+            const ROUTE: &'static [u8] = &[
+                28, 83, 101, 114, 118, 105, 99, 101, 52, 81, 117, 101, 114, 121, 68, 105, 115, 112,
+                117, 116, 101, 115,
+            ];
+            type Params = u32;
+            type Reply = Option<Vec<super::Dispute>>;
+        }
+        pub struct QueryExpenses(());
+        impl QueryExpenses {
+            #[allow(dead_code)]
+            pub fn encode_call(group_id: u32) -> Vec<u8> {
+                <QueryExpenses as ActionIo>::encode_call(&group_id)
+            }
+        }
+        impl ActionIo for QueryExpenses {
+            const ROUTE: &'static [u8] = &[
+                28, 83, 101, 114, 118, 105, 99, 101, 52, 81, 117, 101, 114, 121, 69, 120, 112, 101,
+                110, 115, 101, 115,
+            ];
+            type Params = u32;
+            type Reply = Option<Vec<super::Expense>>;
+        }
+        pub struct QueryGroup(());
         impl QueryGroup {
             #[allow(dead_code)]
             pub fn encode_call(groupId: u32) -> Vec<u8> {
@@ -225,32 +1169,391 @@ pub mod service {
                 112,
             ];
             type Params = u32;
-            type Reply = super::Group;
+            type Reply = super::Group;
+        }
+        pub struct QueryGroupMembers(());
+        impl QueryGroupMembers {
+            #[allow(dead_code)]
+            pub fn encode_call(group_id: u32) -> Vec<u8> {
+                <QueryGroupMembers as ActionIo>::encode_call(&group_id)
+            }
+        }
+        impl ActionIo for QueryGroupMembers {
+            const ROUTE: &'static [u8] = &[
+                28, 83, 101, 114, 118, 105, 99, 101, 68, 81, 117, 101, 114, 121, 71, 114, 111, 117,
+                112, 77, 101, 109, 98, 101, 114, 115,
+            ];
+            type Params = u32;
+            type Reply = Option<Vec<ActorId>>;
+        }
+        pub struct QueryMemberStatement(());
+        impl QueryMemberStatement {
+            #[allow(dead_code)]
+            pub fn encode_call(group_id: u32, actor_id: ActorId) -> Vec<u8> {
+                <QueryMemberStatement as ActionIo>::encode_call(&(group_id, actor_id))
+            }
+        }
+        impl ActionIo for QueryMemberStatement {
+            const ROUTE: &'static [u8] = &[
+                28, 83, 101, 114, 118, 105, 99, 101, 80, 81, 117, 101, 114, 121, 77, 101, 109, 98,
+                101, 114, 83, 116, 97, 116, 101, 109, 101, 110, 116,
+            ];
+            type Params = (u32, ActorId);
+            type Reply = Option<super::MemberStatement>;
+        }
+        pub struct QueryPayments(());
+        impl QueryPayments {
+            #[allow(dead_code)]
+            pub fn encode_call(group_id: u32) -> Vec<u8> {
+                <QueryPayments as ActionIo>::encode_call(&group_id)
+            }
+        }
+        impl ActionIo for QueryPayments {
+            const ROUTE: &'static [u8] = &[
+                28, 83, 101, 114, 118, 105, 99, 101, 52, 81, 117, 101, 114, 121, 80, 97, 121, 109,
+                101, 110, 116, 115,
+            ];
+            type Params = u32;
+            type Reply = Option<Vec<super::Payment>>;
+        }
+        pub struct QueryPool(());
+        impl QueryPool {
+            #[allow(dead_code)]
+            pub fn encode_call(group_id: u32) -> Vec<u8> {
+                <QueryPool as ActionIo>::encode_call(&group_id)
+            }
+        }
+        impl ActionIo for QueryPool {
+            const ROUTE: &'static [u8] = &[
+                28, 83, 101, 114, 118, 105, 99, 101, 36, 81, 117, 101, 114, 121, 80, 111, 111, 108,
+            ];
+            type Params = u32;
+            type Reply = Option<Vec<super::PoolBalance>>;
+        }
+        pub struct QueryRecurringExpenses(());
+        impl QueryRecurringExpenses {
+            #[allow(dead_code)]
+            pub fn encode_call(group_id: u32) -> Vec<u8> {
+                <QueryRecurringExpenses as ActionIo>::encode_call(&group_id)
+            }
+        }
+        impl ActionIo for QueryRecurringExpenses {
+            const ROUTE: &'static [u8] = &[
+                28, 83, 101, 114, 118, 105, 99, 101, 88, 81, 117, 101, 114, 121, 82, 101, 99, 117,
+                114, 114, 105, 110, 103, 69, 120, 112, 101, 110, 115, 101, 115,
+            ];
+            type Params = u32;
+            type Reply = Option<Vec<super::RecurringExpense>>;
+        }
+        pub struct QuerySettlementPlan(());
+        impl QuerySettlementPlan {
+            #[allow(dead_code)]
+            pub fn encode_call(group_id: u32) -> Vec<u8> {
+                <QuerySettlementPlan as ActionIo>::encode_call(&group_id)
+            }
+        }
+        impl ActionIo for QuerySettlementPlan {
+            const ROUTE: &'static [u8] = &[
+                28, 83, 101, 114, 118, 105, 99, 101, 76, 81, 117, 101, 114, 121, 83, 101, 116, 116,
+                108, 101, 109, 101, 110, 116, 80, 108, 97, 110,
+            ];
+            type Params = u32;
+            type Reply = Option<Vec<super::Transfer>>;
         }
-        pub struct QueryGroupMembers(());
-        impl QueryGroupMembers {
+        pub struct QueryWithdrawals(());
+        impl QueryWithdrawals {
             #[allow(dead_code)]
             pub fn encode_call(group_id: u32) -> Vec<u8> {
-                <QueryGroupMembers as ActionIo>::encode_call(&group_id)
+                <QueryWithdrawals as ActionIo>::encode_call(&group_id)
             }
         }
-        impl ActionIo for QueryGroupMembers {
+        impl ActionIo for QueryWithdrawals {
             const ROUTE: &'static [u8] = &[
-                28, 83, 101, 114, 118, 105, 99, 101, 68, 81, 117, 101, 114, 121, 71, 114, 111, 117,
-                112, 77, 101, 109, 98, 101, 114, 115,
+                28, 83, 101, 114, 118, 105, 99, 101, 64, 81, 117, 101, 114, 121, 87, 105, 116, 104,
+                100, 114, 97, 119, 97, 108, 115,
             ];
             type Params = u32;
-            type Reply = Option<Vec<ActorId>>;
+            type Reply = Vec<super::WithdrawalProposal>;
+        }
+    }
+
+    #[allow(dead_code)]
+    #[cfg(not(target_arch = "wasm32"))]
+    pub mod events {
+        use super::*;
+        use sails_rs::events::*;
+        #[derive(PartialEq, Debug, Encode, Decode)]
+        #[codec(crate = sails_rs::scale_codec)]
+        pub enum ServiceEvents {
+            ProgramPaused,
+            ProgramUnpaused,
+            GroupCreated {
+                group_id: u32,
+                name: String,
+                creator: ActorId,
+            },
+            MemberJoined {
+                group_id: u32,
+                actor_id: ActorId,
+            },
+            GroupUpdated {
+                group_id: u32,
+                metadata: GroupMetadataDto,
+            },
+            AdminAdded {
+                group_id: u32,
+                actor_id: ActorId,
+            },
+            GroupClosed {
+                group_id: u32,
+                forced: bool,
+            },
+            ExpenseAdded {
+                group_id: u32,
+                expense: Expense,
+            },
+            RecurringExpenseCreated {
+                group_id: u32,
+                recurring: RecurringExpense,
+            },
+            RecurringExpensePaused {
+                group_id: u32,
+                recurring_id: u32,
+            },
+            RecurringExpenseResumed {
+                group_id: u32,
+                recurring_id: u32,
+            },
+            RecurringExpenseCancelled {
+                group_id: u32,
+                recurring_id: u32,
+            },
+            RecurringExpenseStopped {
+                group_id: u32,
+                recurring_id: u32,
+                reason: String,
+            },
+            ApprovalPolicySet {
+                group_id: u32,
+                policy: ApprovalPolicy,
+            },
+            ExpenseApprovalAdded {
+                group_id: u32,
+                expense_id: u32,
+                actor_id: ActorId,
+            },
+            ExpenseApproved {
+                group_id: u32,
+                expense_id: u32,
+            },
+            ExpenseRejected {
+                group_id: u32,
+                expense_id: u32,
+                actor_id: ActorId,
+            },
+            DisputeOpened {
+                group_id: u32,
+                dispute: Dispute,
+            },
+            DisputeResolved {
+                group_id: u32,
+                dispute_id: u32,
+                status: DisputeStatus,
+                expense_status: ExpenseStatus,
+            },
+            BudgetSet {
+                group_id: u32,
+                budget: Budget,
+            },
+            BudgetRemoved {
+                group_id: u32,
+                category: Option<ExpenseCategory>,
+                currency: String,
+            },
+            PaymentAdded {
+                group_id: u32,
+                payment: Payment,
+            },
+            PaymentConfirmed {
+                group_id: u32,
+                payment_id: u32,
+            },
+            PaymentRejected {
+                group_id: u32,
+                payment_id: u32,
+            },
+            Deposited {
+                group_id: u32,
+                actor_id: ActorId,
+                amount: u128,
+                fee: u128,
+            },
+            PoolPaid {
+                group_id: u32,
+                payment: PoolPayment,
+            },
+            WithdrawalQuorumSet {
+                group_id: u32,
+                quorum: u32,
+            },
+            WithdrawalProposed {
+                proposal: WithdrawalProposal,
+            },
+            WithdrawalApproved {
+                group_id: u32,
+                proposal_id: u32,
+                actor_id: ActorId,
+            },
+            WithdrawalExecuted {
+                group_id: u32,
+                proposal_id: u32,
+                to: ActorId,
+                amount: u128,
+            },
+            BudgetThresholdReached {
+                group_id: u32,
+                category: Option<ExpenseCategory>,
+                threshold: u8,
+                spent: u128,
+                limit: u128,
+            },
+            BudgetExceeded {
+                group_id: u32,
+                category: Option<ExpenseCategory>,
+                spent: u128,
+                limit: u128,
+            },
+        }
+        impl EventIo for ServiceEvents {
+            const ROUTE: &'static [u8] = &[28, 83, 101, 114, 118, 105, 99, 101];
+            const EVENT_NAMES: &'static [&'static [u8]] = &[
+                &[
+                    52, 80, 114, 111, 103, 114, 97, 109, 80, 97, 117, 115, 101, 100,
+                ],
+                &[
+                    60, 80, 114, 111, 103, 114, 97, 109, 85, 110, 112, 97, 117, 115, 101, 100,
+                ],
+                &[48, 71, 114, 111, 117, 112, 67, 114, 101, 97, 116, 101, 100],
+                &[48, 77, 101, 109, 98, 101, 114, 74, 111, 105, 110, 101, 100],
+                &[48, 71, 114, 111, 117, 112, 85, 112, 100, 97, 116, 101, 100],
+                &[40, 65, 100, 109, 105, 110, 65, 100, 100, 101, 100],
+                &[44, 71, 114, 111, 117, 112, 67, 108, 111, 115, 101, 100],
+                &[48, 69, 120, 112, 101, 110, 115, 101, 65, 100, 100, 101, 100],
+                &[
+                    92, 82, 101, 99, 117, 114, 114, 105, 110, 103, 69, 120, 112, 101, 110, 115,
+                    101, 67, 114, 101, 97, 116, 101, 100,
+                ],
+                &[
+                    88, 82, 101, 99, 117, 114, 114, 105, 110, 103, 69, 120, 112, 101, 110, 115,
+                    101, 80, 97, 117, 115, 101, 100,
+                ],
+                &[
+                    92, 82, 101, 99, 117, 114, 114, 105, 110, 103, 69, 120, 112, 101, 110, 115,
+                    101, 82, 101, 115, 117, 109, 101, 100,
+                ],
+                &[
+                    100, 82, 101, 99, 117, 114, 114, 105, 110, 103, 69, 120, 112, 101, 110, 115,
+                    101, 67, 97, 110, 99, 101, 108, 108, 101, 100,
+                ],
+                &[
+                    92, 82, 101, 99, 117, 114, 114, 105, 110, 103, 69, 120, 112, 101, 110, 115,
+                    101, 83, 116, 111, 112, 112, 101, 100,
+                ],
+                &[
+                    68, 65, 112, 112, 114, 111, 118, 97, 108, 80, 111, 108, 105, 99, 121, 83, 101,
+                    116,
+                ],
+                &[
+                    80, 69, 120, 112, 101, 110, 115, 101, 65, 112, 112, 114, 111, 118, 97, 108, 65,
+                    100, 100, 101, 100,
+                ],
+                &[
+                    60, 69, 120, 112, 101, 110, 115, 101, 65, 112, 112, 114, 111, 118, 101, 100,
+                ],
+                &[
+                    60, 69, 120, 112, 101, 110, 115, 101, 82, 101, 106, 101, 99, 116, 101, 100,
+                ],
+                &[
+                    52, 68, 105, 115, 112, 117, 116, 101, 79, 112, 101, 110, 101, 100,
+                ],
+                &[
+                    60, 68, 105, 115, 112, 117, 116, 101, 82, 101, 115, 111, 108, 118, 101, 100,
+                ],
+                &[36, 66, 117, 100, 103, 101, 116, 83, 101, 116],
+                &[
+                    52, 66, 117, 100, 103, 101, 116, 82, 101, 109, 111, 118, 101, 100,
+                ],
+                &[48, 80, 97, 121, 109, 101, 110, 116, 65, 100, 100, 101, 100],
+                &[
+                    64, 80, 97, 121, 109, 101, 110, 116, 67, 111, 110, 102, 105, 114, 109, 101, 100,
+                ],
+                &[
+                    60, 80, 97, 121, 109, 101, 110, 116, 82, 101, 106, 101, 99, 116, 101, 100,
+                ],
+                &[36, 68, 101, 112, 111, 115, 105, 116, 101, 100],
+                &[32, 80, 111, 111, 108, 80, 97, 105, 100],
+                &[
+                    76, 87, 105, 116, 104, 100, 114, 97, 119, 97, 108, 81, 117, 111, 114, 117, 109,
+                    83, 101, 116,
+                ],
+                &[
+                    72, 87, 105, 116, 104, 100, 114, 97, 119, 97, 108, 80, 114, 111, 112, 111, 115,
+                    101, 100,
+                ],
+                &[
+                    72, 87, 105, 116, 104, 100, 114, 97, 119, 97, 108, 65, 112, 112, 114, 111, 118,
+                    101, 100,
+                ],
+                &[
+                    72, 87, 105, 116, 104, 100, 114, 97, 119, 97, 108, 69, 120, 101, 99, 117, 116,
+                    101, 100,
+                ],
+                &[
+                    88, 66, 117, 100, 103, 101, 116, 84, 104, 114, 101, 115, 104, 111, 108, 100,
+                    82, 101, 97, 99, 104, 101, 100,
+                ],
+                &[
+                    56, 66, 117, 100, 103, 101, 116, 69, 120, 99, 101, 101, 100, 101, 100,
+                ],
+            ];
+            type Event = Self;
+        }
+        pub fn listener<R: Listener<Vec<u8>>>(remoting: R) -> impl Listener<ServiceEvents> {
+            RemotingListener::<_, ServiceEvents>::new(remoting)
         }
     }
 }
 #[derive(PartialEq, Clone, Debug, Encode, Decode, TypeInfo)]
 #[codec(crate = sails_rs::scale_codec)]
 #[scale_info(crate = sails_rs::scale_info)]
-pub struct ExpenseDto {
-    pub description: String,
-    pub amount: u128,
-    pub currency: String,
+pub struct Config {
+    pub admin: ActorId,
+    pub allowed_currencies: Vec<String>,
+    pub limits: Limits,
+    pub fees: FeeSettings,
+}
+#[derive(PartialEq, Clone, Debug, Encode, Decode, TypeInfo)]
+#[codec(crate = sails_rs::scale_codec)]
+#[scale_info(crate = sails_rs::scale_info)]
+pub struct Limits {
+    pub max_name_length: u32,
+    pub max_description_length: u32,
+    pub max_currency_length: u32,
+    pub max_uri_length: u32,
+    pub max_tags: u32,
+    pub max_members: u32,
+    pub max_expenses: u32,
+    pub max_payments: u32,
+    pub max_disputes: u32,
+    pub max_budgets: u32,
+    pub max_recurring_expenses: u32,
+}
+#[derive(PartialEq, Clone, Debug, Encode, Decode, TypeInfo)]
+#[codec(crate = sails_rs::scale_codec)]
+#[scale_info(crate = sails_rs::scale_info)]
+pub struct FeeSettings {
+    pub fee_bps: u16,
+    pub fee_collector: ActorId,
 }
 #[derive(PartialEq, Clone, Debug, Encode, Decode, TypeInfo)]
 #[codec(crate = sails_rs::scale_codec)]
@@ -260,13 +1563,118 @@ pub enum Events {
     UserJoined((ActorId, u32)),
     ExpenseAdded((u32, u32)),
     PaymentAdded((u32, u32)),
+    RecurringExpenseCreated((u32, u32)),
+    RecurringExpensePaused((u32, u32)),
+    RecurringExpenseResumed((u32, u32)),
+    RecurringExpenseCancelled((u32, u32)),
+    RecurringExpenseStopped((u32, u32)),
+    BudgetSet(u32),
+    BudgetRemoved(u32),
+    ApprovalPolicySet(u32),
+    ExpenseApprovalAdded((u32, u32)),
+    ExpenseApproved((u32, u32)),
+    ExpenseRejected((u32, u32)),
+    DisputeOpened((u32, u32)),
+    DisputeResolved((u32, u32)),
+    PaymentConfirmed((u32, u32)),
+    PaymentRejected((u32, u32)),
+    Deposited((u32, u128)),
+    PoolPaid((u32, u128)),
+    AdminAdded((ActorId, u32)),
+    WithdrawalQuorumSet((u32, u32)),
+    WithdrawalProposed((u32, u32)),
+    WithdrawalApproved((u32, u32)),
+    WithdrawalExecuted((u32, u32)),
+    GroupClosed(u32),
+    GroupUpdated(u32),
+    ExpensesBatchAdded((u32, u32)),
+    BatchApplied((u32, Vec<u32>)),
+    LimitExceeded(LimitError),
+    ProgramPaused,
+    ProgramUnpaused,
     Error(String),
 }
 #[derive(PartialEq, Clone, Debug, Encode, Decode, TypeInfo)]
 #[codec(crate = sails_rs::scale_codec)]
 #[scale_info(crate = sails_rs::scale_info)]
+pub enum LimitError {
+    NameTooLong,
+    DescriptionTooLong,
+    CurrencyTooLong,
+    UriTooLong,
+    TooManyTags,
+    TooManyMembers,
+    TooManyExpenses,
+    TooManyPayments,
+    TooManyDisputes,
+    TooManyBudgets,
+    TooManyRecurringExpenses,
+}
+#[derive(PartialEq, Clone, Debug, Encode, Decode, TypeInfo)]
+#[codec(crate = sails_rs::scale_codec)]
+#[scale_info(crate = sails_rs::scale_info)]
+pub struct ExpenseDto {
+    pub description: String,
+    pub amount: u128,
+    pub currency: String,
+    pub category: ExpenseCategory,
+    pub tags: Vec<String>,
+    pub participants: Vec<ActorId>,
+}
+#[derive(PartialEq, Clone, Debug, Encode, Decode, TypeInfo)]
+#[codec(crate = sails_rs::scale_codec)]
+#[scale_info(crate = sails_rs::scale_info)]
+pub enum ExpenseCategory {
+    Food,
+    Transport,
+    Lodging,
+    Utilities,
+    Entertainment,
+    Other(String),
+}
+#[derive(PartialEq, Clone, Debug, Encode, Decode, TypeInfo)]
+#[codec(crate = sails_rs::scale_codec)]
+#[scale_info(crate = sails_rs::scale_info)]
+pub enum GroupAction {
+    AddExpense(ExpenseDto),
+    AddPayment { amount: u32, to: ActorId },
+    ApproveExpense(u32),
+    ConfirmPayment(u32),
+}
+#[derive(PartialEq, Clone, Debug, Encode, Decode, TypeInfo)]
+#[codec(crate = sails_rs::scale_codec)]
+#[scale_info(crate = sails_rs::scale_info)]
+pub enum DisputeResolution {
+    Uphold,
+    Amend(u128),
+    Void,
+}
+#[derive(PartialEq, Clone, Debug, Encode, Decode, TypeInfo)]
+#[codec(crate = sails_rs::scale_codec)]
+#[scale_info(crate = sails_rs::scale_info)]
+pub struct GroupMetadataDto {
+    pub name: String,
+    pub description: String,
+    pub avatar_uri: String,
+    pub avatar_hash: Option<H256>,
+    pub default_split_mode: SplitMode,
+    pub default_currency: String,
+}
+#[derive(PartialEq, Clone, Debug, Encode, Decode, TypeInfo)]
+#[codec(crate = sails_rs::scale_codec)]
+#[scale_info(crate = sails_rs::scale_info)]
+pub enum SplitMode {
+    Equal,
+    Percentage,
+    Exact,
+}
+#[derive(PartialEq, Clone, Debug, Encode, Decode, TypeInfo)]
+#[codec(crate = sails_rs::scale_codec)]
+#[scale_info(crate = sails_rs::scale_info)]
 pub struct IoState {
     pub groups: Vec<Group>,
+    pub withdrawals: Vec<WithdrawalProposal>,
+    pub archived_groups: Vec<ArchivedGroup>,
 }
 #[derive(PartialEq, Clone, Debug, Encode, Decode, TypeInfo)]
 #[codec(crate = sails_rs::scale_codec)]
@@ -274,9 +1682,22 @@ pub struct IoState {
 pub struct Group {
     pub id: u32,
     pub name: String,
+    pub description: String,
+    pub avatar_uri: String,
+    pub avatar_hash: Option<H256>,
+    pub default_split_mode: SplitMode,
+    pub default_currency: String,
+    pub admins: Vec<ActorId>,
     pub members: Vec<ActorId>,
     pub expenses: Vec<Expense>,
     pub payments: Vec<Payment>,
+    pub recurring_expenses: Vec<RecurringExpense>,
+    pub budgets: Vec<Budget>,
+    pub approval_policy: ApprovalPolicy,
+    pub disputes: Vec<Dispute>,
+    pub pool: Vec<PoolBalance>,
+    pub pool_payments: Vec<PoolPayment>,
+    pub withdrawal_quorum: u32,
 }
 #[derive(PartialEq, Clone, Debug, Encode, Decode, TypeInfo)]
 #[codec(crate = sails_rs::scale_codec)]
@@ -286,7 +1707,23 @@ pub struct Expense {
     pub description: String,
     pub amount: u128,
     pub currency: String,
+    pub category: ExpenseCategory,
+    pub tags: Vec<String>,
     pub actor_id: ActorId,
+    pub participants: Vec<ActorId>,
+    pub status: ExpenseStatus,
+    pub approvals: Vec<ActorId>,
+    pub created_block: u32,
+    pub created_at: u64,
+}
+#[derive(PartialEq, Clone, Debug, Encode, Decode, TypeInfo)]
+#[codec(crate = sails_rs::scale_codec)]
+#[scale_info(crate = sails_rs::scale_info)]
+pub enum ExpenseStatus {
+    Approved,
+    Pending,
+    Rejected,
+    Voided,
 }
 #[derive(PartialEq, Clone, Debug, Encode, Decode, TypeInfo)]
 #[codec(crate = sails_rs::scale_codec)]
@@ -296,6 +1733,176 @@ pub struct Payment {
     pub from: ActorId,
     pub to: ActorId,
     pub amount: u32,
+    pub status: PaymentStatus,
+    pub created_block: u32,
+    pub created_at: u64,
+}
+#[derive(PartialEq, Clone, Debug, Encode, Decode, TypeInfo)]
+#[codec(crate = sails_rs::scale_codec)]
+#[scale_info(crate = sails_rs::scale_info)]
+pub enum PaymentStatus {
+    Unconfirmed,
+    Confirmed,
+    Rejected,
+}
+#[derive(PartialEq, Clone, Debug, Encode, Decode, TypeInfo)]
+#[codec(crate = sails_rs::scale_codec)]
+#[scale_info(crate = sails_rs::scale_info)]
+pub struct RecurringExpense {
+    pub id: u32,
+    pub creator: ActorId,
+    pub expense: ExpenseDto,
+    pub period_blocks: u32,
+    pub remaining: u32,
+    pub paused: bool,
+    pub next_block: u32,
+    pub nonce: u32,
+}
+#[derive(PartialEq, Clone, Debug, Encode, Decode, TypeInfo)]
+#[codec(crate = sails_rs::scale_codec)]
+#[scale_info(crate = sails_rs::scale_info)]
+pub struct Budget {
+    pub category: Option<ExpenseCategory>,
+    pub currency: String,
+    pub limit: u128,
+    pub period_blocks: u32,
+    pub period_start: u32,
+    pub spent: u128,
+    pub thresholds: Vec<u8>,
+    pub reached: Vec<u8>,
+    pub exceeded: bool,
+    pub counted: Vec<(u32, u128)>,
+}
+#[derive(PartialEq, Clone, Debug, Encode, Decode, TypeInfo)]
+#[codec(crate = sails_rs::scale_codec)]
+#[scale_info(crate = sails_rs::scale_info)]
+pub struct ApprovalPolicy {
+    pub threshold: Option<u128>,
+    pub required_approvals: u32,
+}
+#[derive(PartialEq, Clone, Debug, Encode, Decode, TypeInfo)]
+#[codec(crate = sails_rs::scale_codec)]
+#[scale_info(crate = sails_rs::scale_info)]
+pub struct Dispute {
+    pub id: u32,
+    pub expense_id: u32,
+    pub opened_by: ActorId,
+    pub reason: String,
+    pub status: DisputeStatus,
+}
+#[derive(PartialEq, Clone, Debug, Encode, Decode, TypeInfo)]
+#[codec(crate = sails_rs::scale_codec)]
+#[scale_info(crate = sails_rs::scale_info)]
+pub enum DisputeStatus {
+    Open,
+    Upheld,
+    Amended(u128),
+    Voided,
+}
+#[derive(PartialEq, Clone, Debug, Encode, Decode, TypeInfo)]
+#[codec(crate = sails_rs::scale_codec)]
+#[scale_info(crate = sails_rs::scale_info)]
+pub struct PoolBalance {
+    pub actor_id: ActorId,
+    pub amount: u128,
+}
+#[derive(PartialEq, Clone, Debug, Encode, Decode, TypeInfo)]
+#[codec(crate = sails_rs::scale_codec)]
+#[scale_info(crate = sails_rs::scale_info)]
+pub struct PoolPayment {
+    pub id: u32,
+    pub vendor: ActorId,
+    pub amount: u128,
+    pub description: String,
+    pub paid_by: ActorId,
+}
+#[derive(PartialEq, Clone, Debug, Encode, Decode, TypeInfo)]
+#[codec(crate = sails_rs::scale_codec)]
+#[scale_info(crate = sails_rs::scale_info)]
+pub struct WithdrawalProposal {
+    pub id: u32,
+    pub group_id: u32,
+    pub proposer: ActorId,
+    pub to: ActorId,
+    pub amount: u128,
+    pub approvals: Vec<ActorId>,
+    pub expires_at: u32,
+    pub executed: bool,
+    pub description: Option<String>,
+}
+#[derive(PartialEq, Clone, Debug, Encode, Decode, TypeInfo)]
+#[codec(crate = sails_rs::scale_codec)]
+#[scale_info(crate = sails_rs::scale_info)]
+pub struct ArchivedGroup {
+    pub id: u32,
+    pub name: String,
+    pub members: Vec<ActorId>,
+    pub closed_at: u32,
+    pub expense_count: u32,
+    pub payment_count: u32,
+    pub totals: Vec<CategoryTotal>,
+    pub final_balances: Vec<Balance>,
+    pub settlement: Vec<Transfer>,
+}
+#[derive(PartialEq, Clone, Debug, Encode, Decode, TypeInfo)]
+#[codec(crate = sails_rs::scale_codec)]
+#[scale_info(crate = sails_rs::scale_info)]
+pub struct CategoryTotal {
+    pub category: ExpenseCategory,
+    pub currency: String,
+    pub total: u128,
+}
+#[derive(PartialEq, Clone, Debug, Encode, Decode, TypeInfo)]
+#[codec(crate = sails_rs::scale_codec)]
+#[scale_info(crate = sails_rs::scale_info)]
+pub struct Balance {
+    pub actor_id: ActorId,
+    pub amount: i128,
+}
+#[derive(PartialEq, Clone, Debug, Encode, Decode, TypeInfo)]
+#[codec(crate = sails_rs::scale_codec)]
+#[scale_info(crate = sails_rs::scale_info)]
+pub struct Transfer {
+    pub from: ActorId,
+    pub to: ActorId,
+    pub amount: u128,
+}
+#[derive(PartialEq, Clone, Debug, Encode, Decode, TypeInfo)]
+#[codec(crate = sails_rs::scale_codec)]
+#[scale_info(crate = sails_rs::scale_info)]
+pub struct BudgetStatus {
+    pub category: Option<ExpenseCategory>,
+    pub currency: String,
+    pub limit: u128,
+    pub spent: u128,
+    pub period_start: u32,
+    pub period_end: u32,
+    pub exceeded: bool,
+}
+#[derive(PartialEq, Clone, Debug, Encode, Decode, TypeInfo)]
+#[codec(crate = sails_rs::scale_codec)]
+#[scale_info(crate = sails_rs::scale_info)]
+pub struct MemberStatement {
+    pub actor_id: ActorId,
+    pub lines: Vec<StatementLine>,
+    pub balance: i128,
+}
+#[derive(PartialEq, Clone, Debug, Encode, Decode, TypeInfo)]
+#[codec(crate = sails_rs::scale_codec)]
+#[scale_info(crate = sails_rs::scale_info)]
+pub struct StatementLine {
+    pub entry: StatementEntry,
+    pub amount: i128,
+    pub balance: i128,
+}
+#[derive(PartialEq, Clone, Debug, Encode, Decode, TypeInfo)]
+#[codec(crate = sails_rs::scale_codec)]
+#[scale_info(crate = sails_rs::scale_info)]
+pub enum StatementEntry {
+    ExpensePaid(u32),
+    ExpenseShare(u32),
+    PaymentSent(u32),
+    PaymentReceived(u32),
 }
 
 pub mod traits {
@@ -306,29 +1913,196 @@ pub mod traits {
         #[allow(clippy::new_ret_no_self)]
         #[allow(clippy::wrong_self_convention)]
         fn new(&self) -> impl Activation<Args = Self::Args>;
+        #[allow(clippy::new_ret_no_self)]
+        #[allow(clippy::wrong_self_convention)]
+        fn new_from_snapshot(&self, snapshot: Vec<u8>) -> impl Activation<Args = Self::Args>;
+        #[allow(clippy::new_ret_no_self)]
+        #[allow(clippy::wrong_self_convention)]
+        fn new_with_admin(&self, admin: ActorId) -> impl Activation<Args = Self::Args>;
+        #[allow(clippy::new_ret_no_self)]
+        #[allow(clippy::wrong_self_convention)]
+        fn new_with_config(&self, config: Config) -> impl Activation<Args = Self::Args>;
     }
 
     #[allow(clippy::type_complexity)]
     pub trait Service {
         type Args;
+        fn add_admin(
+            &mut self,
+            group_id: u32,
+            member: ActorId,
+        ) -> impl Call<Output = Events, Args = Self::Args>;
         fn add_expense(
             &mut self,
             group_id: u32,
             expenseDTO: ExpenseDto,
         ) -> impl Call<Output = Events, Args = Self::Args>;
+        fn add_expenses_batch(
+            &mut self,
+            group_id: u32,
+            expenses: Vec<ExpenseDto>,
+        ) -> impl Call<Output = Events, Args = Self::Args>;
         fn add_payment(
             &mut self,
             group_id: u32,
             amount: u32,
             to: ActorId,
         ) -> impl Call<Output = Events, Args = Self::Args>;
+        fn approve_expense(
+            &mut self,
+            group_id: u32,
+            expense_id: u32,
+        ) -> impl Call<Output = Events, Args = Self::Args>;
+        fn approve_withdrawal(
+            &mut self,
+            proposal_id: u32,
+        ) -> impl Call<Output = Events, Args = Self::Args>;
+        fn batch(
+            &mut self,
+            group_id: u32,
+            actions: Vec<GroupAction>,
+        ) -> impl Call<Output = Events, Args = Self::Args>;
+        fn cancel_recurring_expense(
+            &mut self,
+            group_id: u32,
+            recurring_id: u32,
+        ) -> impl Call<Output = Events, Args = Self::Args>;
+        fn close_group(
+            &mut self,
+            group_id: u32,
+            force: bool,
+        ) -> impl Call<Output = Events, Args = Self::Args>;
+        fn confirm_payment(
+            &mut self,
+            group_id: u32,
+            payment_id: u32,
+        ) -> impl Call<Output = Events, Args = Self::Args>;
         fn create_group(
             &mut self,
             group_name: String,
         ) -> impl Call<Output = Events, Args = Self::Args>;
+        fn create_recurring_expense(
+            &mut self,
+            group_id: u32,
+            expense_dto: ExpenseDto,
+            period_blocks: u32,
+            occurrences: u32,
+        ) -> impl Call<Output = Events, Args = Self::Args>;
+        fn deposit(&mut self, group_id: u32) -> impl Call<Output = Events, Args = Self::Args>;
+        fn execute_withdrawal(
+            &mut self,
+            proposal_id: u32,
+        ) -> impl Call<Output = Events, Args = Self::Args>;
         fn join_group(&mut self, group_id: u32) -> impl Call<Output = Events, Args = Self::Args>;
+        fn open_dispute(
+            &mut self,
+            group_id: u32,
+            expense_id: u32,
+            reason: String,
+        ) -> impl Call<Output = Events, Args = Self::Args>;
+        fn pause(&mut self) -> impl Call<Output = Events, Args = Self::Args>;
+        fn pause_recurring_expense(
+            &mut self,
+            group_id: u32,
+            recurring_id: u32,
+        ) -> impl Call<Output = Events, Args = Self::Args>;
+        fn pay_from_pool(
+            &mut self,
+            group_id: u32,
+            vendor: ActorId,
+            amount: u128,
+            description: String,
+        ) -> impl Call<Output = Events, Args = Self::Args>;
+        fn process_recurring_expense(
+            &mut self,
+            group_id: u32,
+            recurring_id: u32,
+            nonce: u32,
+        ) -> impl Call<Output = Events, Args = Self::Args>;
+        fn propose_withdrawal(
+            &mut self,
+            group_id: u32,
+            to: ActorId,
+            amount: u128,
+            expiry_blocks: u32,
+        ) -> impl Call<Output = Events, Args = Self::Args>;
+        fn reject_expense(
+            &mut self,
+            group_id: u32,
+            expense_id: u32,
+        ) -> impl Call<Output = Events, Args = Self::Args>;
+        fn reject_payment(
+            &mut self,
+            group_id: u32,
+            payment_id: u32,
+        ) -> impl Call<Output = Events, Args = Self::Args>;
+        fn remove_budget(
+            &mut self,
+            group_id: u32,
+            category: Option<ExpenseCategory>,
+            currency: String,
+        ) -> impl Call<Output = Events, Args = Self::Args>;
+        fn resolve_dispute(
+            &mut self,
+            group_id: u32,
+            dispute_id: u32,
+            resolution: DisputeResolution,
+        ) -> impl Call<Output = Events, Args = Self::Args>;
+        fn resume_recurring_expense(
+            &mut self,
+            group_id: u32,
+            recurring_id: u32,
+        ) -> impl Call<Output = Events, Args = Self::Args>;
+        fn set_approval_policy(
+            &mut self,
+            group_id: u32,
+            threshold: Option<u128>,
+            required_approvals: u32,
+        ) -> impl Call<Output = Events, Args = Self::Args>;
+        fn set_budget(
+            &mut self,
+            group_id: u32,
+            category: Option<ExpenseCategory>,
+            currency: String,
+            limit: u128,
+            period_blocks: u32,
+            thresholds: Vec<u8>,
+        ) -> impl Call<Output = Events, Args = Self::Args>;
+        fn set_withdrawal_quorum(
+            &mut self,
+            group_id: u32,
+            quorum: u32,
+        ) -> impl Call<Output = Events, Args = Self::Args>;
+        fn unpause(&mut self) -> impl Call<Output = Events, Args = Self::Args>;
+        fn update_group_metadata(
+            &mut self,
+            group_id: u32,
+            metadata: GroupMetadataDto,
+        ) -> impl Call<Output = Events, Args = Self::Args>;
+        fn export_state(&self) -> impl Query<Output = Vec<u8>, Args = Self::Args>;
         fn query(&self) -> impl Query<Output = IoState, Args = Self::Args>;
         fn query_actor_groups(&self) -> impl Query<Output = Vec<Group>, Args = Self::Args>;
+        fn query_archived_group(
+            &self,
+            group_id: u32,
+        ) -> impl Query<Output = Option<ArchivedGroup>, Args = Self::Args>;
+        fn query_balances(
+            &self,
+            group_id: u32,
+        ) -> impl Query<Output = Option<Vec<Balance>>, Args = Self::Args>;
+        fn query_budget_status(
+            &self,
+            group_id: u32,
+        ) -> impl Query<Output = Option<Vec<BudgetStatus>>, Args = Self::Args>;
+        fn query_category_totals(
+            &self,
+            group_id: u32,
+        ) -> impl Query<Output = Option<Vec<CategoryTotal>>, Args = Self::Args>;
+        fn query_config(&self) -> impl Query<Output = Config, Args = Self::Args>;
+        fn query_disputes(
+            &self,
+            group_id: u32,
+        ) -> impl Query<Output = Option<Vec<Dispute>>, Args = Self::Args>;
         fn query_expenses(
             &self,
             group_id: u32,
@@ -338,5 +2112,30 @@ pub mod traits {
             &self,
             group_id: u32,
         ) -> impl Query<Output = Option<Vec<ActorId>>, Args = Self::Args>;
+        fn query_member_statement(
+            &self,
+            group_id: u32,
+            actor_id: ActorId,
+        ) -> impl Query<Output = Option<MemberStatement>, Args = Self::Args>;
+        fn query_payments(
+            &self,
+            group_id: u32,
+        ) -> impl Query<Output = Option<Vec<Payment>>, Args = Self::Args>;
+        fn query_pool(
+            &self,
+            group_id: u32,
+        ) -> impl Query<Output = Option<Vec<PoolBalance>>, Args = Self::Args>;
+        fn query_recurring_expenses(
+            &self,
+            group_id: u32,
+        ) -> impl Query<Output = Option<Vec<RecurringExpense>>, Args = Self::Args>;
+        fn query_settlement_plan(
+            &self,
+            group_id: u32,
+        ) -> impl Query<Output = Option<Vec<Transfer>>, Args = Self::Args>;
+        fn query_withdrawals(
+            &self,
+            group_id: u32,
+        ) -> impl Query<Output = Vec<WithdrawalProposal>, Args = Self::Args>;
     }
 }
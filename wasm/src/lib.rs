@@ -2,3 +2,12 @@
 
 #[cfg(target_arch = "wasm32")]
 pub use app::wasm::*;
+
+// Optimized binary of the program, used by the host-side crates to deploy it
+#[cfg(not(target_arch = "wasm32"))]
+pub use code::WASM_BINARY_OPT as WASM_BINARY;
+
+#[cfg(not(target_arch = "wasm32"))]
+mod code {
+    include!(concat!(env!("OUT_DIR"), "/wasm_binary.rs"));
+}